@@ -0,0 +1,20 @@
+ALTER TABLE
+  excluded_artists
+ADD
+  COLUMN reason TEXT NULL COMMENT '除外した理由'
+AFTER
+  name,
+ADD
+  COLUMN category ENUM('disliked', 'already_known', 'wrong_genre', 'explicit', 'other') NULL COMMENT '除外の分類'
+AFTER
+  reason,
+ADD
+  COLUMN expires_at DATETIME NULL COMMENT '除外の有効期限 (この日時以降は除外されていないものとして扱う)'
+AFTER
+  category,
+ADD
+  COLUMN source_music_genre_id INT UNSIGNED NULL COMMENT '除外のきっかけになったmusic_genresテーブルのID'
+AFTER
+  expires_at,
+ADD
+  CONSTRAINT fk_excluded_artists_music_genres FOREIGN KEY (source_music_genre_id) REFERENCES music_genres (id) ON DELETE SET NULL;
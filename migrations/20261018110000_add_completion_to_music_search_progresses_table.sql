@@ -0,0 +1,10 @@
+ALTER TABLE
+  music_search_progresses
+ADD
+  COLUMN last_total INT UNSIGNED NULL COMMENT '最後に取得した検索結果の総数'
+AFTER
+  position,
+ADD
+  COLUMN completed_at DATETIME NULL COMMENT '検索を最後まで終えた日時'
+AFTER
+  last_total;
@@ -0,0 +1,10 @@
+-- 除外されているアーティスト
+CREATE TABLE
+  excluded_artists (
+    -- SpotifyのアーティストID
+    id TEXT NOT NULL PRIMARY KEY,
+    -- アーティスト名
+    name TEXT NOT NULL,
+    -- 作成日時
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+  );
@@ -0,0 +1,27 @@
+-- 音楽ジャンル
+CREATE TABLE
+  music_genres (
+    -- ID
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- ジャンル名
+    name TEXT NOT NULL,
+    -- 検索キー
+    search_key TEXT NOT NULL,
+    -- 作成日時
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- 更新日時
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT uk_music_genres_search_key UNIQUE (search_key)
+  );
+
+-- SQLite には ON UPDATE CURRENT_TIMESTAMP がないためトリガーで更新する
+CREATE TRIGGER
+  tr_music_genres_updated_at AFTER
+UPDATE ON music_genres FOR EACH ROW WHEN NEW.updated_at = OLD.updated_at BEGIN
+UPDATE music_genres
+SET
+  updated_at = CURRENT_TIMESTAMP
+WHERE
+  id = NEW.id;
+
+END;
@@ -0,0 +1,28 @@
+-- 音楽検索の進捗
+CREATE TABLE
+  music_search_progresses (
+    -- ID
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- music_genresテーブルのID
+    music_genre_id INTEGER NOT NULL,
+    -- 検索の現在の位置
+    position INTEGER NOT NULL CHECK (position >= 0),
+    -- 作成日時
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- 更新日時
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_music_search_progresses_music_genres FOREIGN KEY (music_genre_id) REFERENCES music_genres (id),
+    CONSTRAINT uk_music_search_progresses_music_genre_id UNIQUE (music_genre_id)
+  );
+
+-- SQLite には ON UPDATE CURRENT_TIMESTAMP がないためトリガーで更新する
+CREATE TRIGGER
+  tr_music_search_progresses_updated_at AFTER
+UPDATE ON music_search_progresses FOR EACH ROW WHEN NEW.updated_at = OLD.updated_at BEGIN
+UPDATE music_search_progresses
+SET
+  updated_at = CURRENT_TIMESTAMP
+WHERE
+  id = NEW.id;
+
+END;
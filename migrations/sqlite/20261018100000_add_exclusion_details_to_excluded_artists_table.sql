@@ -0,0 +1,23 @@
+-- 除外した理由
+ALTER TABLE excluded_artists
+ADD COLUMN reason TEXT NULL;
+
+-- 除外の分類
+ALTER TABLE excluded_artists
+ADD COLUMN category TEXT NULL CHECK (
+  category IN (
+    'disliked',
+    'already_known',
+    'wrong_genre',
+    'explicit',
+    'other'
+  )
+);
+
+-- 除外の有効期限 (この日時以降は除外されていないものとして扱う)
+ALTER TABLE excluded_artists
+ADD COLUMN expires_at DATETIME NULL;
+
+-- 除外のきっかけになったmusic_genresテーブルのID
+ALTER TABLE excluded_artists
+ADD COLUMN source_music_genre_id INTEGER NULL REFERENCES music_genres (id) ON DELETE SET NULL;
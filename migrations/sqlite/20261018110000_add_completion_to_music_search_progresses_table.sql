@@ -0,0 +1,7 @@
+-- 最後に取得した検索結果の総数
+ALTER TABLE music_search_progresses
+ADD COLUMN last_total INTEGER NULL;
+
+-- 検索を最後まで終えた日時
+ALTER TABLE music_search_progresses
+ADD COLUMN completed_at DATETIME NULL;
@@ -1,2 +1,119 @@
 pub mod api;
+mod batch;
+mod cassette;
+pub mod error;
+pub mod http;
+pub mod pagination;
+pub mod rate_limiter;
+mod retry;
+pub mod token_manager;
 pub mod v1;
+
+use crate::config::{RetryConfig, SpotifyConfig};
+use anyhow::Result;
+use error::SpotifyError;
+use http::HttpClient;
+use rate_limiter::RateLimiter;
+use reqwest::{Method, RequestBuilder, Response};
+use retry::{Decision, Idempotency};
+use serde::de::DeserializeOwned;
+use token_manager::TokenManager;
+use tokio::time::sleep;
+
+#[derive(Clone)]
+pub struct SpotifyClient {
+    http: HttpClient,
+    api_base_url: String,
+    token_manager: TokenManager,
+    retry: RetryConfig,
+    rate_limiter: RateLimiter,
+    batch_concurrency: usize,
+}
+
+impl SpotifyClient {
+    pub fn new(config: &SpotifyConfig) -> Result<Self> {
+        let http = HttpClient::new(config)?;
+
+        Ok(Self {
+            token_manager: TokenManager::new(http.clone(), config.clone())?,
+            http,
+            api_base_url: config.api_base_url.clone(),
+            retry: config.retry.clone(),
+            rate_limiter: RateLimiter::new(config.rate_limit.clone()),
+            batch_concurrency: config.batch_concurrency,
+        })
+    }
+
+    pub fn token_manager(&self) -> &TokenManager {
+        &self.token_manager
+    }
+
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}{}", self.api_base_url, path))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, SpotifyError> {
+        self.execute(request, Idempotency::NonIdempotent).await
+    }
+
+    // PUT/DELETE のうち、繰り返しても結果が変わらないものに使う
+    async fn send_idempotent(&self, request: RequestBuilder) -> Result<Response, SpotifyError> {
+        self.execute(request, Idempotency::Idempotent).await
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, SpotifyError> {
+        let response = self.execute(request, Idempotency::Idempotent).await?;
+
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    async fn execute(
+        &self,
+        request: RequestBuilder,
+        idempotency: Idempotency,
+    ) -> Result<Response, SpotifyError> {
+        let mut attempt = 0;
+        let mut token_refreshed = false;
+        loop {
+            let access_token = self.token_manager.access_token().await?;
+            self.rate_limiter.acquire().await;
+            let result = match self
+                .http
+                .send(
+                    request
+                        .try_clone()
+                        .expect("リクエストボディはストリームではないため複製できる")
+                        .bearer_auth(&access_token),
+                )
+                .await
+            {
+                Ok(response) => error::check(response).await,
+                Err(e) => Err(e),
+            };
+            let error = match result {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            match retry::decide(&self.retry, &error, idempotency, attempt, token_refreshed) {
+                Decision::RefreshToken => {
+                    self.token_manager.invalidate(&access_token).await;
+                    token_refreshed = true;
+                }
+                Decision::RetryAfter(delay) => {
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                Decision::GiveUp => return Err(error),
+            }
+        }
+    }
+}
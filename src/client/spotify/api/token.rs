@@ -1,31 +1,100 @@
-use anyhow::Result;
+use crate::{
+    client::spotify::{
+        error::{self, SpotifyError},
+        http::HttpClient,
+    },
+    config::SpotifyConfig,
+};
 use base64::prelude::*;
-use reqwest::Client;
+use reqwest::{Method, RequestBuilder};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct PostResponse {
     pub access_token: String,
+    pub token_type: String,
+    pub scope: Option<String>,
+    pub expires_in: u64,
+    pub refresh_token: Option<String>,
 }
 
-pub async fn post() -> Result<PostResponse> {
-    let refresh_token = env!("SPOTIFY_REFRESH_TOKEN");
+pub async fn post(
+    client: &HttpClient,
+    config: &SpotifyConfig,
+    refresh_token: &str,
+    pkce: bool,
+) -> Result<PostResponse, SpotifyError> {
     let params = [
         ("grant_type", "refresh_token"),
-        ("refresh_token", &refresh_token),
+        ("refresh_token", refresh_token),
     ];
-    let client_id = env!("SPOTIFY_CLIENT_ID");
-    let client_secret = env!("SPOTIFY_CLIENT_SECRET");
-    let authorization = BASE64_STANDARD.encode(format!("{}:{}", client_id, client_secret));
-    let client = Client::new();
-
-    Ok(client
-        .post("https://accounts.spotify.com/api/token")
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .header("Authorization", format!("Basic {}", authorization))
-        .form(&params)
-        .send()
-        .await?
-        .json::<PostResponse>()
-        .await?)
+
+    send(client, config, &params, pkce).await
+}
+
+pub struct AuthorizationCodeParams<'a> {
+    pub code: &'a str,
+    pub redirect_uri: &'a str,
+    pub code_verifier: &'a str,
+}
+
+pub async fn post_authorization_code(
+    client: &HttpClient,
+    config: &SpotifyConfig,
+    params: &AuthorizationCodeParams<'_>,
+) -> Result<PostResponse, SpotifyError> {
+    let params = [
+        ("grant_type", "authorization_code"),
+        ("code", params.code),
+        ("redirect_uri", params.redirect_uri),
+        ("code_verifier", params.code_verifier),
+    ];
+
+    send(client, config, &params, true).await
+}
+
+async fn send(
+    client: &HttpClient,
+    config: &SpotifyConfig,
+    params: &[(&str, &str)],
+    pkce: bool,
+) -> Result<PostResponse, SpotifyError> {
+    let request = client
+        .request(
+            Method::POST,
+            format!("{}/api/token", config.accounts_base_url),
+        )
+        .header("Content-Type", "application/x-www-form-urlencoded");
+
+    let response = error::check(
+        client
+            .send(authorize(request, config, params, pkce))
+            .await?,
+    )
+    .await?;
+
+    Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+// PKCEで取得したトークンやシークレット未設定の場合は公開クライアントとして client_id を送る
+fn authorize(
+    request: RequestBuilder,
+    config: &SpotifyConfig,
+    params: &[(&str, &str)],
+    pkce: bool,
+) -> RequestBuilder {
+    match config.client_secret.as_ref().filter(|_| !pkce) {
+        Some(client_secret) => {
+            let authorization =
+                BASE64_STANDARD.encode(format!("{}:{}", config.client_id, client_secret));
+            request
+                .header("Authorization", format!("Basic {}", authorization))
+                .form(params)
+        }
+        None => {
+            let mut params = params.to_vec();
+            params.push(("client_id", &config.client_id));
+            request.form(&params)
+        }
+    }
 }
@@ -0,0 +1,26 @@
+use crate::client::spotify::error::SpotifyError;
+use futures::{StreamExt, TryStreamExt, stream};
+
+// IDの上限があるエンドポイント向けに分割して並行実行し、結果を元の順序で結合する
+pub(crate) async fn batched<T, F, Fut>(
+    ids: &[String],
+    chunk_size: usize,
+    concurrency: usize,
+    request: F,
+) -> Result<Vec<T>, SpotifyError>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, SpotifyError>>,
+{
+    let chunks = ids
+        .chunks(chunk_size)
+        .map(|chunk| chunk.to_vec())
+        .collect::<Vec<_>>();
+    let results = stream::iter(chunks)
+        .map(request)
+        .buffered(concurrency)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(results.into_iter().flatten().collect())
+}
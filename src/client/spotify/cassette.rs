@@ -0,0 +1,264 @@
+use crate::{
+    client::spotify::error::SpotifyError,
+    config::{CassetteConfig, CassetteMode},
+};
+use anyhow::{Context, Result};
+use reqwest::{
+    Client, Request, Response,
+    header::{CONTENT_TYPE, RETRY_AFTER},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Mutex};
+
+const REDACTED: &str = "REDACTED";
+// 記録するときに値を伏せるフォームのキーとレスポンスのフィールド
+const SECRET_FORM_KEYS: &[&str] = &[
+    "client_id",
+    "client_secret",
+    "refresh_token",
+    "code",
+    "code_verifier",
+];
+const SECRET_RESPONSE_FIELDS: &[&str] = &["access_token", "refresh_token"];
+
+#[derive(Default, Serialize, Deserialize)]
+struct Fixture {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+// Authorization ヘッダーは記録せず、照合にも使わない
+#[derive(PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    // ホストを含めないパスとクエリ。接続先を変えても再生できるようにする
+    url: String,
+    body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    // JSON はそのまま、それ以外は文字列として保存する
+    body: Value,
+}
+
+struct State {
+    fixture: Fixture,
+    // 再生済みのやり取り。同じリクエストが複数回あれば記録した順に返す
+    played: Vec<bool>,
+}
+
+pub struct Cassette {
+    mode: CassetteMode,
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    pub fn open(config: &CassetteConfig) -> Result<Self> {
+        let fixture = match config.mode {
+            CassetteMode::Record => Fixture::default(),
+            CassetteMode::Replay => {
+                let content = fs::read_to_string(&config.path).with_context(|| {
+                    format!(
+                        "記録ファイルの読み込みに失敗しました: {}",
+                        config.path.display()
+                    )
+                })?;
+                serde_json::from_str(&content).with_context(|| {
+                    format!("記録ファイルの形式が不正です: {}", config.path.display())
+                })?
+            }
+        };
+
+        Ok(Self {
+            mode: config.mode,
+            path: config.path.clone(),
+            state: Mutex::new(State {
+                played: vec![false; fixture.interactions.len()],
+                fixture,
+            }),
+        })
+    }
+
+    pub async fn send(&self, client: &Client, request: Request) -> Result<Response, SpotifyError> {
+        let recorded = RecordedRequest::new(&request);
+        match self.mode {
+            CassetteMode::Replay => self.replay(&recorded),
+            CassetteMode::Record => {
+                let response = client.execute(request).await?;
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes().await?;
+                self.record(
+                    recorded,
+                    RecordedResponse::new(status.as_u16(), &headers, &body),
+                )?;
+
+                // 呼び出し元には伏せる前の本物のレスポンスを返す
+                let mut builder = http::Response::builder().status(status);
+                for (name, value) in &headers {
+                    builder = builder.header(name, value);
+                }
+                build(builder, body.to_vec())
+            }
+        }
+    }
+
+    fn replay(&self, request: &RecordedRequest) -> Result<Response, SpotifyError> {
+        let mut state = self.state();
+        let State { fixture, played } = &mut *state;
+        let Some(index) = fixture
+            .interactions
+            .iter()
+            .zip(played.iter())
+            .position(|(interaction, played)| !played && interaction.request == *request)
+        else {
+            return Err(SpotifyError::Cassette(format!(
+                "記録されていないリクエストです: {} {}{} ({})",
+                request.method,
+                request.url,
+                request
+                    .body
+                    .as_ref()
+                    .map(|body| format!(" {}", body))
+                    .unwrap_or_default(),
+                self.path.display()
+            )));
+        };
+        played[index] = true;
+
+        let response = &fixture.interactions[index].response;
+        let mut builder = http::Response::builder().status(response.status);
+        for (name, value) in &response.headers {
+            builder = builder.header(name, value);
+        }
+        let body = match &response.body {
+            Value::Null => Vec::new(),
+            Value::String(body) => body.clone().into_bytes(),
+            body => body.to_string().into_bytes(),
+        };
+
+        build(builder, body)
+    }
+
+    // 途中で終了しても記録が残るように、やり取りのたびにファイルへ書き出す
+    fn record(
+        &self,
+        request: RecordedRequest,
+        response: RecordedResponse,
+    ) -> Result<(), SpotifyError> {
+        let mut state = self.state();
+        state
+            .fixture
+            .interactions
+            .push(Interaction { request, response });
+        state.played.push(true);
+        let content = serde_json::to_string_pretty(&state.fixture)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| self.write_error(e))?;
+        }
+        fs::write(&self.path, content + "\n").map_err(|e| self.write_error(e))?;
+
+        Ok(())
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("ロックを保持したままパニックしない")
+    }
+
+    fn write_error(&self, e: std::io::Error) -> SpotifyError {
+        SpotifyError::Cassette(format!(
+            "記録ファイルの書き込みに失敗しました: {} ({})",
+            self.path.display(),
+            e
+        ))
+    }
+}
+
+impl RecordedRequest {
+    fn new(request: &Request) -> Self {
+        let url = request.url();
+        let url = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| String::from_utf8_lossy(body).into_owned());
+        let form = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .is_some_and(|value| value == "application/x-www-form-urlencoded");
+
+        Self {
+            method: request.method().to_string(),
+            url,
+            body: match form {
+                true => body.map(|body| scrub_form(&body)),
+                false => body,
+            },
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn new(status: u16, headers: &reqwest::header::HeaderMap, body: &[u8]) -> Self {
+        let headers = [CONTENT_TYPE, RETRY_AFTER]
+            .iter()
+            .filter_map(|name| {
+                let value = headers.get(name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+        let body = match serde_json::from_slice::<Value>(body) {
+            Ok(mut body) => {
+                if let Some(body) = body.as_object_mut() {
+                    for field in SECRET_RESPONSE_FIELDS {
+                        if let Some(value) = body.get_mut(*field) {
+                            *value = Value::from(REDACTED);
+                        }
+                    }
+                }
+                body
+            }
+            Err(_) if body.is_empty() => Value::Null,
+            Err(_) => Value::from(String::from_utf8_lossy(body).into_owned()),
+        };
+
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+}
+
+fn scrub_form(body: &str) -> String {
+    body.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if SECRET_FORM_KEYS.contains(&key) => format!("{}={}", key, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn build(builder: http::response::Builder, body: Vec<u8>) -> Result<Response, SpotifyError> {
+    let response = builder
+        .body(body)
+        .map_err(|e| SpotifyError::Cassette(format!("レスポンスを組み立てられません: {}", e)))?;
+
+    Ok(Response::from(response))
+}
@@ -0,0 +1,186 @@
+use reqwest::{Response, StatusCode, header::RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PlayerErrorReason {
+    NoPrevTrack,
+    NoNextTrack,
+    NoSpecificTrack,
+    AlreadyPaused,
+    NotPaused,
+    NotPlayingLocally,
+    NotPlayingTrack,
+    NotPlayingContext,
+    EndlessContext,
+    ContextDisallow,
+    AlreadyPlaying,
+    RateLimited,
+    RemoteControlDisallow,
+    DeviceNotControllable,
+    VolumeControlDisallow,
+    NoActiveDevice,
+    PremiumRequired,
+    #[serde(other)]
+    Unknown,
+}
+
+impl PlayerErrorReason {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NoPrevTrack => "前の曲がありません",
+            Self::NoNextTrack => "次の曲がありません",
+            Self::NoSpecificTrack => "指定された曲を再生できません",
+            Self::AlreadyPaused => "すでに一時停止しています",
+            Self::NotPaused => "一時停止していません",
+            Self::NotPlayingLocally => "ローカルで再生していません",
+            Self::NotPlayingTrack => "曲を再生していません",
+            Self::NotPlayingContext => "コンテキストを再生していません",
+            Self::EndlessContext => "終わりのないコンテキストでは操作できません",
+            Self::ContextDisallow => "現在のコンテキストでは操作できません",
+            Self::AlreadyPlaying => "すでに再生しています",
+            Self::RateLimited => "操作の頻度が高すぎます",
+            Self::RemoteControlDisallow => "このデバイスはリモート操作できません",
+            Self::DeviceNotControllable => "このデバイスは操作できません",
+            Self::VolumeControlDisallow => "このデバイスは音量を変更できません",
+            Self::NoActiveDevice => "アクティブなデバイスがありません",
+            Self::PremiumRequired => "Spotify Premium が必要です",
+            Self::Unknown => "不明な理由で操作できません",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SpotifyError {
+    #[error("認証に失敗しました: {message}")]
+    Unauthorized { message: String },
+    #[error("権限がありません: {}", describe(message, reason))]
+    Forbidden {
+        message: String,
+        reason: Option<PlayerErrorReason>,
+    },
+    #[error("見つかりません: {}", describe(message, reason))]
+    NotFound {
+        message: String,
+        reason: Option<PlayerErrorReason>,
+    },
+    #[error("レート制限に達しました")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("Spotify のサーバーエラーです ({status}): {message}")]
+    Server { status: u16, message: String },
+    #[error("Spotify API のエラーです ({status}): {}", describe(message, reason))]
+    Api {
+        status: u16,
+        message: String,
+        reason: Option<PlayerErrorReason>,
+    },
+    #[error("通信に失敗しました: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("レスポンスの解析に失敗しました: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("記録したやり取りを使えません: {0}")]
+    Cassette(String),
+}
+
+impl SpotifyError {
+    pub async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response.text().await.unwrap_or_default();
+
+        Self::from_parts(status, retry_after, &body)
+    }
+
+    fn from_parts(status: StatusCode, retry_after: Option<Duration>, body: &str) -> Self {
+        let (message, reason) = match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(ErrorResponse {
+                error: ErrorBody::Regular { message, reason },
+                ..
+            }) => (message, reason),
+            // トークンエンドポイントは OAuth 形式のエラーを返す
+            Ok(ErrorResponse {
+                error: ErrorBody::OAuth(error),
+                error_description,
+            }) => (error_description.unwrap_or(error), None),
+            Err(_) if !body.trim().is_empty() => (body.trim().to_string(), None),
+            Err(_) => (
+                status.canonical_reason().unwrap_or_default().to_string(),
+                None,
+            ),
+        };
+
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized { message },
+            StatusCode::FORBIDDEN => Self::Forbidden { message, reason },
+            StatusCode::NOT_FOUND => Self::NotFound { message, reason },
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { retry_after },
+            status if status.is_server_error() => Self::Server {
+                status: status.as_u16(),
+                message,
+            },
+            status => Self::Api {
+                status: status.as_u16(),
+                message,
+                reason,
+            },
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Unauthorized { .. } => Some(StatusCode::UNAUTHORIZED.as_u16()),
+            Self::Forbidden { .. } => Some(StatusCode::FORBIDDEN.as_u16()),
+            Self::NotFound { .. } => Some(StatusCode::NOT_FOUND.as_u16()),
+            Self::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS.as_u16()),
+            Self::Server { status, .. } | Self::Api { status, .. } => Some(*status),
+            Self::Http(e) => e.status().map(|status| status.as_u16()),
+            Self::Decode(_) | Self::Cassette(_) => None,
+        }
+    }
+
+    pub fn reason(&self) -> Option<PlayerErrorReason> {
+        match self {
+            Self::Forbidden { reason, .. }
+            | Self::NotFound { reason, .. }
+            | Self::Api { reason, .. } => *reason,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorBody,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorBody {
+    Regular {
+        message: String,
+        reason: Option<PlayerErrorReason>,
+    },
+    OAuth(String),
+}
+
+fn describe(message: &str, reason: &Option<PlayerErrorReason>) -> String {
+    match reason {
+        Some(reason) => format!("{} ({})", reason.description(), message),
+        None => message.to_string(),
+    }
+}
+
+pub(crate) async fn check(response: Response) -> Result<Response, SpotifyError> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(SpotifyError::from_response(response).await)
+    }
+}
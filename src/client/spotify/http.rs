@@ -0,0 +1,43 @@
+use crate::{
+    client::spotify::{cassette::Cassette, error::SpotifyError},
+    config::SpotifyConfig,
+};
+use anyhow::Result;
+use reqwest::{Client, IntoUrl, Method, RequestBuilder, Response};
+use std::sync::Arc;
+
+// Spotify への通信はすべてここを通し、設定に応じて記録・再生に切り替える
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    cassette: Option<Arc<Cassette>>,
+}
+
+impl HttpClient {
+    pub fn new(config: &SpotifyConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.timeout)
+            .build()?;
+        let cassette = config
+            .cassette
+            .as_ref()
+            .map(Cassette::open)
+            .transpose()?
+            .map(Arc::new);
+
+        Ok(Self { client, cassette })
+    }
+
+    pub fn request(&self, method: Method, url: impl IntoUrl) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, SpotifyError> {
+        let request = request.build()?;
+        match &self.cassette {
+            Some(cassette) => cassette.send(&self.client, request).await,
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::client::spotify::error::SpotifyError;
+use futures::{
+    Stream, StreamExt, TryStreamExt,
+    stream::{self, BoxStream},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct Page<T> {
+    pub href: String,
+    pub limit: u32,
+    pub next: Option<String>,
+    pub offset: u32,
+    pub previous: Option<String>,
+    pub total: u32,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CursorPage<T> {
+    pub href: String,
+    pub limit: u32,
+    pub next: Option<String>,
+    pub cursors: Option<Cursors>,
+    pub total: Option<u32>,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Cursors {
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageCursor {
+    Offset(u32),
+    After(String),
+}
+
+pub trait Paged {
+    type Item;
+
+    fn next_cursor(&self) -> Option<PageCursor>;
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+impl<T> Paged for Page<T> {
+    type Item = T;
+
+    fn next_cursor(&self) -> Option<PageCursor> {
+        self.next
+            .as_ref()
+            .map(|_| PageCursor::Offset(self.offset + self.items.len() as u32))
+    }
+
+    fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T> Paged for CursorPage<T> {
+    type Item = T;
+
+    fn next_cursor(&self) -> Option<PageCursor> {
+        self.next.as_ref()?;
+        self.cursors
+            .as_ref()
+            .and_then(|cursors| cursors.after.clone())
+            .map(PageCursor::After)
+    }
+
+    fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+// `fetch` には最初のページでは None、以降は前のページから得たカーソルが渡される
+pub fn paginate<'a, P, F, Fut>(
+    max_items: Option<usize>,
+    mut fetch: F,
+) -> BoxStream<'a, Result<P::Item, SpotifyError>>
+where
+    P: Paged + Send + 'a,
+    P::Item: Send + 'a,
+    F: FnMut(Option<PageCursor>) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<P, SpotifyError>> + Send + 'a,
+{
+    let pages = stream::try_unfold(Some(None), move |cursor: Option<Option<PageCursor>>| {
+        let request = cursor.map(&mut fetch);
+        async move {
+            let Some(request) = request else {
+                return Ok(None);
+            };
+            let page = request.await?;
+            let next = page.next_cursor();
+            let items = page.into_items();
+            // 空のページが返った場合は次のページがあっても打ち切る
+            let cursor = next.filter(|_| !items.is_empty()).map(Some);
+
+            Ok(Some((items, cursor)))
+        }
+    });
+    let items = flatten(pages);
+
+    match max_items {
+        Some(max_items) => items.take(max_items).boxed(),
+        None => items.boxed(),
+    }
+}
+
+fn flatten<'a, T: Send + 'a>(
+    pages: impl Stream<Item = Result<Vec<T>, SpotifyError>> + Send + 'a,
+) -> impl Stream<Item = Result<T, SpotifyError>> + Send + 'a {
+    pages
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+}
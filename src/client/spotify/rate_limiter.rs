@@ -0,0 +1,101 @@
+use crate::config::RateLimitConfig;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::Mutex,
+    time::{Instant, sleep},
+};
+
+struct Bucket {
+    // 待機中のリクエストに予約された分だけ負になる
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+struct Inner {
+    config: RateLimitConfig,
+    bucket: Mutex<Bucket>,
+    acquired: AtomicU64,
+    throttled: AtomicU64,
+    waiting: AtomicUsize,
+}
+
+#[derive(Debug)]
+pub struct RateLimiterState {
+    pub burst: u32,
+    pub requests_per_second: f64,
+    pub available_tokens: f64,
+    pub waiting: usize,
+    pub acquired: u64,
+    pub throttled: u64,
+}
+
+#[derive(Clone)]
+pub struct RateLimiter {
+    inner: Arc<Inner>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                bucket: Mutex::new(Bucket {
+                    tokens: f64::from(config.burst),
+                    refilled_at: Instant::now(),
+                }),
+                config,
+                acquired: AtomicU64::new(0),
+                throttled: AtomicU64::new(0),
+                waiting: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.inner.bucket.lock().await;
+            self.refill(&mut bucket);
+            bucket.tokens -= 1.0;
+            (bucket.tokens < 0.0).then(|| {
+                Duration::from_secs_f64(-bucket.tokens / self.inner.config.requests_per_second)
+            })
+        };
+        self.inner.acquired.fetch_add(1, Ordering::Relaxed);
+        if let Some(wait) = wait {
+            self.inner.throttled.fetch_add(1, Ordering::Relaxed);
+            self.inner.waiting.fetch_add(1, Ordering::Relaxed);
+            sleep(wait).await;
+            self.inner.waiting.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    pub async fn state(&self) -> RateLimiterState {
+        let available_tokens = {
+            let mut bucket = self.inner.bucket.lock().await;
+            self.refill(&mut bucket);
+            bucket.tokens
+        };
+
+        RateLimiterState {
+            burst: self.inner.config.burst,
+            requests_per_second: self.inner.config.requests_per_second,
+            available_tokens,
+            waiting: self.inner.waiting.load(Ordering::Relaxed),
+            acquired: self.inner.acquired.load(Ordering::Relaxed),
+            throttled: self.inner.throttled.load(Ordering::Relaxed),
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.inner.config.requests_per_second)
+            .min(f64::from(self.inner.config.burst));
+        bucket.refilled_at = now;
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{client::spotify::error::SpotifyError, config::RetryConfig};
+use rand::Rng;
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Idempotency {
+    // 同じリクエストを繰り返しても結果が変わらない
+    Idempotent,
+    // サーバーに届いていないことが確実な場合のみ再試行する
+    NonIdempotent,
+}
+
+pub(crate) enum Decision {
+    RefreshToken,
+    RetryAfter(Duration),
+    GiveUp,
+}
+
+pub(crate) fn decide(
+    config: &RetryConfig,
+    error: &SpotifyError,
+    idempotency: Idempotency,
+    attempt: u32,
+    token_refreshed: bool,
+) -> Decision {
+    if let SpotifyError::Unauthorized { .. } = error {
+        return if token_refreshed {
+            Decision::GiveUp
+        } else {
+            Decision::RefreshToken
+        };
+    }
+    if attempt >= config.max_retries {
+        return Decision::GiveUp;
+    }
+    let idempotent = idempotency == Idempotency::Idempotent;
+
+    match error {
+        SpotifyError::RateLimited {
+            retry_after: Some(retry_after),
+        } if *retry_after > config.max_backoff => Decision::GiveUp,
+        SpotifyError::RateLimited {
+            retry_after: Some(retry_after),
+        } => Decision::RetryAfter(*retry_after),
+        SpotifyError::RateLimited { retry_after: None } => {
+            Decision::RetryAfter(backoff(config, attempt))
+        }
+        SpotifyError::Server { .. } if idempotent => Decision::RetryAfter(backoff(config, attempt)),
+        SpotifyError::Http(e) if e.is_connect() || (idempotent && e.is_timeout()) => {
+            Decision::RetryAfter(backoff(config, attempt))
+        }
+        _ => Decision::GiveUp,
+    }
+}
+
+// Full Jitter 方式の指数バックオフ
+fn backoff(config: &RetryConfig, attempt: u32) -> Duration {
+    let ceiling = config
+        .initial_backoff
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(config.max_backoff);
+
+    rand::thread_rng().gen_range(Duration::ZERO..=ceiling)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RetryConfig {
+        RetryConfig {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    fn server_error() -> SpotifyError {
+        SpotifyError::Server {
+            status: 503,
+            message: "Service unavailable".to_string(),
+        }
+    }
+
+    #[test]
+    fn unauthorized_refreshes_token_once() {
+        let error = SpotifyError::Unauthorized {
+            message: "The access token expired".to_string(),
+        };
+
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::NonIdempotent, 3, false),
+            Decision::RefreshToken
+        ));
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::Idempotent, 0, true),
+            Decision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn rate_limited_waits_for_retry_after() {
+        let error = SpotifyError::RateLimited {
+            retry_after: Some(Duration::from_secs(2)),
+        };
+
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::NonIdempotent, 0, false),
+            Decision::RetryAfter(delay) if delay == Duration::from_secs(2)
+        ));
+    }
+
+    #[test]
+    fn rate_limited_gives_up_when_retry_after_exceeds_max_backoff() {
+        let error = SpotifyError::RateLimited {
+            retry_after: Some(Duration::from_secs(60)),
+        };
+
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::Idempotent, 0, false),
+            Decision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn rate_limited_without_retry_after_backs_off() {
+        let error = SpotifyError::RateLimited { retry_after: None };
+
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::Idempotent, 2, false),
+            Decision::RetryAfter(delay) if delay <= Duration::from_millis(400)
+        ));
+    }
+
+    #[test]
+    fn server_error_retries_only_idempotent_requests() {
+        assert!(matches!(
+            decide(
+                &config(),
+                &server_error(),
+                Idempotency::Idempotent,
+                0,
+                false
+            ),
+            Decision::RetryAfter(_)
+        ));
+        assert!(matches!(
+            decide(
+                &config(),
+                &server_error(),
+                Idempotency::NonIdempotent,
+                0,
+                false
+            ),
+            Decision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let error = SpotifyError::RateLimited { retry_after: None };
+
+        assert!(matches!(
+            decide(
+                &config(),
+                &server_error(),
+                Idempotency::Idempotent,
+                3,
+                false
+            ),
+            Decision::GiveUp
+        ));
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::Idempotent, 3, false),
+            Decision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn client_error_is_not_retried() {
+        let error = SpotifyError::Api {
+            status: 400,
+            message: "Bad request".to_string(),
+            reason: None,
+        };
+
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::Idempotent, 0, false),
+            Decision::GiveUp
+        ));
+    }
+
+    #[tokio::test]
+    async fn connection_error_is_retried_even_if_not_idempotent() {
+        // 使われていないポートに接続して接続エラーを起こす
+        let error = SpotifyError::Http(reqwest::get("http://127.0.0.1:1").await.unwrap_err());
+
+        assert!(matches!(
+            decide(&config(), &error, Idempotency::NonIdempotent, 0, false),
+            Decision::RetryAfter(_)
+        ));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max_backoff() {
+        let config = config();
+
+        for _ in 0..100 {
+            assert!(backoff(&config, 0) <= Duration::from_millis(100));
+            assert!(backoff(&config, 3) <= Duration::from_millis(800));
+            assert!(backoff(&config, 20) <= Duration::from_secs(10));
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    client::spotify::{api::token, error::SpotifyError, http::HttpClient},
+    config::SpotifyConfig,
+    constant::spotify::{ACCESS_TOKEN_REFRESH_MARGIN_SECS, ACCESS_TOKEN_RETRY_INTERVAL_SECS},
+    infrastructure::credentials::Credentials,
+};
+use anyhow::{Context, Result};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+    time::{Instant, sleep},
+};
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+impl CachedToken {
+    fn is_fresh(&self) -> bool {
+        self.expires_at > Instant::now() + Duration::from_secs(ACCESS_TOKEN_REFRESH_MARGIN_SECS)
+    }
+}
+
+struct RefreshToken {
+    value: String,
+    pkce: bool,
+}
+
+struct Inner {
+    http: HttpClient,
+    config: SpotifyConfig,
+    cached: RwLock<Option<CachedToken>>,
+    // 更新処理はこのロックを取得したタスクだけが行う
+    refresh_token: Mutex<RefreshToken>,
+}
+
+#[derive(Clone)]
+pub struct TokenManager {
+    inner: Arc<Inner>,
+}
+
+impl TokenManager {
+    pub fn new(http: HttpClient, config: SpotifyConfig) -> Result<Self> {
+        let refresh_token = match Credentials::load(&config.credentials_path)? {
+            Some(credentials) => RefreshToken {
+                value: credentials.refresh_token,
+                pkce: credentials.pkce,
+            },
+            None => RefreshToken {
+                value: config.refresh_token.clone().context(
+                    "リフレッシュトークンがありません。`spotify-mcp login` を実行するか SPOTIFY_REFRESH_TOKEN を設定してください",
+                )?,
+                pkce: false,
+            },
+        };
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                http,
+                config,
+                cached: RwLock::new(None),
+                refresh_token: Mutex::new(refresh_token),
+            }),
+        })
+    }
+
+    pub async fn access_token(&self) -> Result<String, SpotifyError> {
+        if let Some(access_token) = self.fresh_access_token().await {
+            return Ok(access_token);
+        }
+
+        let mut refresh_token = self.inner.refresh_token.lock().await;
+        if let Some(access_token) = self.fresh_access_token().await {
+            return Ok(access_token);
+        }
+        let response = token::post(
+            &self.inner.http,
+            &self.inner.config,
+            &refresh_token.value,
+            refresh_token.pkce,
+        )
+        .await?;
+        if let Some(rotated) = response.refresh_token {
+            let credentials = Credentials::new(rotated, response.scope, refresh_token.pkce);
+            if let Err(e) = credentials.save(&self.inner.config.credentials_path) {
+                eprintln!("更新されたリフレッシュトークンの保存に失敗しました: {}", e);
+            }
+            refresh_token.value = credentials.refresh_token;
+        }
+        *self.inner.cached.write().await = Some(CachedToken {
+            access_token: response.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+
+        Ok(response.access_token)
+    }
+
+    // 401 が返ったトークンを破棄する。他のタスクが更新済みの場合はそのまま使う
+    pub async fn invalidate(&self, access_token: &str) {
+        let mut cached = self.inner.cached.write().await;
+        if cached
+            .as_ref()
+            .is_some_and(|cached| cached.access_token == access_token)
+        {
+            *cached = None;
+        }
+    }
+
+    pub fn spawn_refresh_task(&self) -> JoinHandle<()> {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = manager.access_token().await {
+                    eprintln!("アクセストークンの更新に失敗しました: {}", e);
+                    sleep(Duration::from_secs(ACCESS_TOKEN_RETRY_INTERVAL_SECS)).await;
+                    continue;
+                }
+                sleep(manager.time_until_refresh().await).await;
+            }
+        })
+    }
+
+    async fn fresh_access_token(&self) -> Option<String> {
+        self.inner
+            .cached
+            .read()
+            .await
+            .as_ref()
+            .filter(|cached| cached.is_fresh())
+            .map(|cached| cached.access_token.clone())
+    }
+
+    async fn time_until_refresh(&self) -> Duration {
+        match self.inner.cached.read().await.as_ref() {
+            Some(cached) => cached
+                .expires_at
+                .saturating_duration_since(Instant::now())
+                .saturating_sub(Duration::from_secs(ACCESS_TOKEN_REFRESH_MARGIN_SECS)),
+            None => Duration::ZERO,
+        }
+    }
+}
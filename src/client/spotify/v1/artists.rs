@@ -1 +1,32 @@
 pub mod top_tracks;
+
+use crate::{
+    client::spotify::{SpotifyClient, batch, error::SpotifyError, v1::search::artist::Artist},
+    constant::spotify::ARTISTS_IDS_LIMIT,
+};
+use reqwest::Method;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct GetResponse {
+    // 存在しないIDは null が返る
+    artists: Vec<Option<Artist>>,
+}
+
+impl SpotifyClient {
+    pub async fn get_artists(&self, ids: &[String]) -> Result<Vec<Option<Artist>>, SpotifyError> {
+        batch::batched(
+            ids,
+            ARTISTS_IDS_LIMIT,
+            self.batch_concurrency,
+            |ids| async move {
+                let request = self
+                    .request(Method::GET, "/v1/artists")
+                    .query(&[("ids", ids.join(","))]);
+
+                Ok(self.send_json::<GetResponse>(request).await?.artists)
+            },
+        )
+        .await
+    }
+}
@@ -1,6 +1,5 @@
-use crate::constant::spotify::API_BASE_URL;
-use anyhow::Result;
-use reqwest::Client;
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -18,18 +17,13 @@ pub struct ExternalUrls {
     pub spotify: String,
 }
 
-pub async fn get(artist_id: &str, access_token: &str) -> Result<Vec<Track>> {
-    let client = Client::new();
+impl SpotifyClient {
+    pub async fn get_artist_top_tracks(&self, artist_id: &str) -> Result<Vec<Track>, SpotifyError> {
+        let request = self.request(
+            Method::GET,
+            &format!("/v1/artists/{}/top-tracks", artist_id),
+        );
 
-    Ok(client
-        .get(&format!(
-            "{}/v1/artists/{}/top-tracks",
-            API_BASE_URL, artist_id
-        ))
-        .bearer_auth(access_token)
-        .send()
-        .await?
-        .json::<GetResponse>()
-        .await?
-        .tracks)
+        Ok(self.send_json::<GetResponse>(request).await?.tracks)
+    }
 }
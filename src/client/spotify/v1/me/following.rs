@@ -1,81 +1,77 @@
 pub mod contains;
 
-use crate::constant::spotify::API_BASE_URL;
-use anyhow::Result;
-use reqwest::Client;
+use crate::{
+    client::spotify::{
+        SpotifyClient, batch,
+        error::SpotifyError,
+        pagination::{self, CursorPage, PageCursor},
+        v1::search::artist::Artist,
+    },
+    constant::spotify::{FOLLOWING_IDS_LIMIT, MAX_PAGE_LIMIT},
+};
+use futures::{TryStreamExt, stream::BoxStream};
+use reqwest::Method;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 struct GetResponse {
-    artists: Artists,
+    artists: CursorPage<Artist>,
 }
 
-#[derive(Debug, Deserialize)]
-struct Artists {
-    cursors: Cursors,
-    items: Vec<Artist>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Cursors {
-    after: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Artist {
-    pub id: String,
+pub enum PutType {
+    Artist,
+    User,
 }
 
-pub async fn get(access_token: &str) -> Result<Vec<Artist>> {
-    let mut artists = Vec::new();
-    let mut after = Some(String::new());
-    let client = Client::new();
-    while let Some(now_after) = after {
-        let query = [("type", "artist"), ("after", &now_after)];
-        let response = client
-            .get(&format!("{}/v1/me/following", API_BASE_URL))
-            .query(&query)
-            .bearer_auth(access_token)
-            .send()
-            .await?
-            .json::<GetResponse>()
-            .await?;
-        after = response.artists.cursors.after;
-        response
-            .artists
-            .items
-            .into_iter()
-            .for_each(|artist| artists.push(artist));
+impl SpotifyClient {
+    pub async fn get_followed_artists(&self) -> Result<Vec<Artist>, SpotifyError> {
+        self.followed_artists_stream(None).try_collect().await
     }
 
-    Ok(artists)
-}
+    pub fn followed_artists_stream(
+        &self,
+        max_items: Option<usize>,
+    ) -> BoxStream<'_, Result<Artist, SpotifyError>> {
+        pagination::paginate(max_items, move |cursor| {
+            let mut query = vec![
+                ("type", "artist".to_string()),
+                ("limit", MAX_PAGE_LIMIT.to_string()),
+            ];
+            if let Some(PageCursor::After(after)) = cursor {
+                query.push(("after", after));
+            }
+            let request = self.request(Method::GET, "/v1/me/following").query(&query);
+            async move { Ok(self.send_json::<GetResponse>(request).await?.artists) }
+        })
+    }
 
-pub enum PutType {
-    Artist,
-    User,
-}
+    pub async fn follow(&self, r#type: PutType, ids: &[String]) -> Result<(), SpotifyError> {
+        let query = [(
+            "type",
+            match r#type {
+                PutType::Artist => "artist",
+                PutType::User => "user",
+            },
+        )];
+        batch::batched(
+            ids,
+            FOLLOWING_IDS_LIMIT,
+            self.batch_concurrency,
+            |ids| async move {
+                let body = serde_json::json!({
+                    "ids": ids
+                });
+                let request = self
+                    .request(Method::PUT, "/v1/me/following")
+                    .query(&query)
+                    .json(&body);
+                self.send_idempotent(request).await?;
 
-pub async fn put(access_token: &str, r#type: PutType, ids: &[String]) -> Result<()> {
-    let client = Client::new();
-    let query = &[(
-        "type",
-        match r#type {
-            PutType::Artist => "artist",
-            PutType::User => "user",
-        },
-    )];
-    let body = serde_json::json!({
-        "ids": ids
-    });
-    client
-        .put(&format!("{}/v1/me/following", API_BASE_URL))
-        .bearer_auth(access_token)
-        .header("Content-Type", "application/json")
-        .query(&query)
-        .json(&body)
-        .send()
+                Ok(Vec::<()>::new())
+            },
+        )
         .await?;
 
-    Ok(())
+        Ok(())
+    }
 }
@@ -1,32 +1,44 @@
-use crate::constant::spotify::API_BASE_URL;
-use anyhow::Result;
-use reqwest::Client;
+use crate::{
+    client::spotify::{SpotifyClient, batch, error::SpotifyError},
+    constant::spotify::FOLLOWING_IDS_LIMIT,
+};
+use reqwest::Method;
 
 type GetResponse = Vec<bool>;
+#[derive(Clone, Copy)]
 pub enum Type {
     Artist,
     User,
 }
 
-pub async fn get(access_token: &str, r#type: Type, ids: &[String]) -> Result<GetResponse> {
-    let client = Client::new();
-    let query = [
-        (
-            "type",
-            match r#type {
-                Type::Artist => "artist",
-                Type::User => "user",
-            },
-        ),
-        ("ids", &ids.join(",")),
-    ];
+impl SpotifyClient {
+    pub async fn check_following(
+        &self,
+        r#type: Type,
+        ids: &[String],
+    ) -> Result<GetResponse, SpotifyError> {
+        batch::batched(
+            ids,
+            FOLLOWING_IDS_LIMIT,
+            self.batch_concurrency,
+            |ids| async move {
+                let query = [
+                    (
+                        "type",
+                        match r#type {
+                            Type::Artist => "artist",
+                            Type::User => "user",
+                        },
+                    ),
+                    ("ids", &ids.join(",")),
+                ];
+                let request = self
+                    .request(Method::GET, "/v1/me/following/contains")
+                    .query(&query);
 
-    Ok(client
-        .get(&format!("{}/v1/me/following/contains", API_BASE_URL))
-        .query(&query)
-        .bearer_auth(access_token)
-        .send()
-        .await?
-        .json::<GetResponse>()
-        .await?)
+                self.send_json::<GetResponse>(request).await
+            },
+        )
+        .await
+    }
 }
@@ -1 +1,43 @@
+pub mod devices;
+pub mod next;
+pub mod pause;
 pub mod play;
+pub mod previous;
+pub mod repeat;
+pub mod seek;
+pub mod shuffle;
+pub mod volume;
+
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::{Method, RequestBuilder};
+
+impl SpotifyClient {
+    // device_id を省略した場合は現在アクティブなデバイスが対象になる
+    // 本文のない PUT/POST でも Content-Length が必要なため空の本文を付ける
+    fn player_request(
+        &self,
+        method: Method,
+        path: &str,
+        device_id: Option<&str>,
+    ) -> RequestBuilder {
+        let request = self
+            .request(method, &format!("/v1/me/player{}", path))
+            .body(Vec::new());
+        match device_id {
+            Some(device_id) => request.query(&[("device_id", device_id)]),
+            None => request,
+        }
+    }
+
+    // play が false の場合は移動前の再生・一時停止の状態を引き継ぐ
+    pub async fn transfer_playback(&self, device_id: &str, play: bool) -> Result<(), SpotifyError> {
+        let body = serde_json::json!({
+            "device_ids": [device_id],
+            "play": play,
+        });
+        let request = self.request(Method::PUT, "/v1/me/player").json(&body);
+        self.send_idempotent(request).await?;
+
+        Ok(())
+    }
+}
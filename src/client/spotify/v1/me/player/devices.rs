@@ -0,0 +1,32 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+struct GetResponse {
+    devices: Vec<Device>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Device {
+    // 制限されたデバイスでは null になることがある
+    pub id: Option<String>,
+    pub is_active: bool,
+    pub is_private_session: bool,
+    // true の場合は API から操作できない
+    pub is_restricted: bool,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub device_type: String,
+    pub volume_percent: Option<u32>,
+    #[serde(default)]
+    pub supports_volume: bool,
+}
+
+impl SpotifyClient {
+    pub async fn get_devices(&self) -> Result<Vec<Device>, SpotifyError> {
+        let request = self.request(Method::GET, "/v1/me/player/devices");
+
+        Ok(self.send_json::<GetResponse>(request).await?.devices)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+
+impl SpotifyClient {
+    pub async fn pause(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
+        let request = self.player_request(Method::PUT, "/pause", device_id);
+        self.send_idempotent(request).await?;
+
+        Ok(())
+    }
+}
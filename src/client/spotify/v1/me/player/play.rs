@@ -1,20 +1,42 @@
-use std::collections::HashMap;
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+use serde::Serialize;
 
-use crate::constant::spotify::API_BASE_URL;
-use anyhow::Result;
-use reqwest::Client;
+#[derive(Default, Serialize)]
+pub struct PutBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uris: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Offset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_ms: Option<u32>,
+}
+
+// コンテキストまたは uris の中で再生を始める曲
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Offset {
+    Position(u32),
+    Uri(String),
+}
+
+impl SpotifyClient {
+    pub async fn play(&self, body: &PutBody, device_id: Option<&str>) -> Result<(), SpotifyError> {
+        let request = self
+            .player_request(Method::PUT, "/play", device_id)
+            .json(body);
+        self.send(request).await?;
+
+        Ok(())
+    }
 
-pub async fn put(access_token: &str, context_uri: &str) -> Result<()> {
-    let client = Client::new();
-    let mut body = HashMap::new();
-    body.insert("context_uri", context_uri);
-    let _ = client
-        .put(&format!("{}/v1/me/player/play", API_BASE_URL))
-        .bearer_auth(access_token)
-        .header("Content-type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    // 本文を付けずに呼ぶと一時停止中の再生を再開する
+    pub async fn resume(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
+        let request = self.player_request(Method::PUT, "/play", device_id);
+        self.send(request).await?;
 
-    Ok(())
+        Ok(())
+    }
 }
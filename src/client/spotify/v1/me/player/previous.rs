@@ -0,0 +1,11 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+
+impl SpotifyClient {
+    pub async fn skip_to_previous(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
+        let request = self.player_request(Method::POST, "/previous", device_id);
+        self.send(request).await?;
+
+        Ok(())
+    }
+}
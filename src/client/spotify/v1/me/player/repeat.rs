@@ -0,0 +1,45 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatState {
+    Track,
+    Context,
+    Off,
+}
+
+impl RepeatState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Track => "track",
+            Self::Context => "context",
+            Self::Off => "off",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Track => "1曲リピート",
+            Self::Context => "アルバム・プレイリストのリピート",
+            Self::Off => "オフ",
+        }
+    }
+}
+
+impl SpotifyClient {
+    pub async fn set_repeat(
+        &self,
+        state: RepeatState,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        let request = self
+            .player_request(Method::PUT, "/repeat", device_id)
+            .query(&[("state", state.as_str())]);
+        self.send_idempotent(request).await?;
+
+        Ok(())
+    }
+}
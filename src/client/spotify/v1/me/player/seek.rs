@@ -0,0 +1,17 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+
+impl SpotifyClient {
+    pub async fn seek(
+        &self,
+        position_ms: u32,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        let request = self
+            .player_request(Method::PUT, "/seek", device_id)
+            .query(&[("position_ms", position_ms)]);
+        self.send_idempotent(request).await?;
+
+        Ok(())
+    }
+}
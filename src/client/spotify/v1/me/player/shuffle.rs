@@ -0,0 +1,17 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+
+impl SpotifyClient {
+    pub async fn set_shuffle(
+        &self,
+        state: bool,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        let request = self
+            .player_request(Method::PUT, "/shuffle", device_id)
+            .query(&[("state", state)]);
+        self.send_idempotent(request).await?;
+
+        Ok(())
+    }
+}
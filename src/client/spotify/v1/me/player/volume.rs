@@ -0,0 +1,17 @@
+use crate::client::spotify::{SpotifyClient, error::SpotifyError};
+use reqwest::Method;
+
+impl SpotifyClient {
+    pub async fn set_volume(
+        &self,
+        volume_percent: u32,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        let request = self
+            .player_request(Method::PUT, "/volume", device_id)
+            .query(&[("volume_percent", volume_percent)]);
+        self.send_idempotent(request).await?;
+
+        Ok(())
+    }
+}
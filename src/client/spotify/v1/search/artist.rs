@@ -1,6 +1,13 @@
-use crate::constant::spotify::API_BASE_URL;
-use anyhow::Result;
-use reqwest::Client;
+use crate::{
+    client::spotify::{
+        SpotifyClient,
+        error::SpotifyError,
+        pagination::{self, Page, PageCursor},
+    },
+    constant::spotify::{MAX_PAGE_LIMIT, SEARCH_OFFSET_LIMIT},
+};
+use futures::stream::BoxStream;
+use reqwest::Method;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -8,16 +15,7 @@ pub struct GetResponse {
     pub artists: ArtistsPage,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ArtistsPage {
-    pub href: String,
-    pub limit: u32,
-    pub next: Option<String>,
-    pub offset: u32,
-    pub previous: Option<String>,
-    pub total: u32,
-    pub items: Vec<Artist>,
-}
+pub type ArtistsPage = Page<Artist>;
 
 #[derive(Debug, Deserialize)]
 pub struct Artist {
@@ -49,8 +47,8 @@ pub struct Followers {
 #[derive(Debug, Deserialize)]
 pub struct Image {
     pub url: String,
-    pub height: u32,
-    pub width: u32,
+    pub height: Option<u32>,
+    pub width: Option<u32>,
 }
 
 pub struct GetQuery {
@@ -59,36 +57,57 @@ pub struct GetQuery {
     pub genre: Option<String>,
 }
 
-pub async fn get(access_token: &str, query: &GetQuery) -> Result<GetResponse> {
-    let client = Client::new();
-    let query = &[
-        ("type", "artist"),
-        (
-            "q",
-            &format!("genre:{}", query.genre.as_deref().unwrap_or(""),),
-        ),
-        (
-            "offset",
-            &query
-                .offset
-                .map(|offset| offset.to_string())
-                .unwrap_or_else(|| String::new()),
-        ),
-        (
-            "limit",
-            &query
-                .limit
-                .map(|limit| limit.to_string())
-                .unwrap_or_else(|| String::new()),
-        ),
-    ];
+impl SpotifyClient {
+    pub async fn search_artists(&self, query: &GetQuery) -> Result<GetResponse, SpotifyError> {
+        let query = [
+            ("type", "artist"),
+            (
+                "q",
+                &format!("genre:{}", query.genre.as_deref().unwrap_or("")),
+            ),
+            (
+                "offset",
+                &query
+                    .offset
+                    .map(|offset| offset.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "limit",
+                &query
+                    .limit
+                    .map(|limit| limit.to_string())
+                    .unwrap_or_default(),
+            ),
+        ];
+
+        let request = self.request(Method::GET, "/v1/search").query(&query);
+
+        self.send_json(request).await
+    }
+
+    // 検索APIは offset が上限を超えるとエラーになるため、その手前で打ち切る
+    pub fn search_artists_stream(
+        &self,
+        genre: &str,
+        offset: u32,
+        max_items: Option<usize>,
+    ) -> BoxStream<'_, Result<Artist, SpotifyError>> {
+        let available = SEARCH_OFFSET_LIMIT.saturating_sub(offset) as usize;
+        let max_items = max_items.map_or(available, |max_items| max_items.min(available));
+        let limit = max_items.min(MAX_PAGE_LIMIT as usize) as u32;
+        let genre = genre.to_string();
 
-    Ok(client
-        .get(&format!("{}/v1/search", API_BASE_URL))
-        .bearer_auth(access_token)
-        .query(&query)
-        .send()
-        .await?
-        .json::<GetResponse>()
-        .await?)
+        pagination::paginate(Some(max_items), move |cursor| {
+            let query = GetQuery {
+                offset: Some(match cursor {
+                    Some(PageCursor::Offset(offset)) => offset,
+                    _ => offset,
+                }),
+                limit: Some(limit),
+                genre: Some(genre.clone()),
+            };
+            async move { Ok(self.search_artists(&query).await?.artists) }
+        })
+    }
 }
@@ -0,0 +1,168 @@
+use crate::{
+    client::spotify::{
+        api::token::{self, AuthorizationCodeParams},
+        http::HttpClient,
+    },
+    config::SpotifyConfig,
+    constant::spotify::{AUTHORIZATION_SCOPES, LOGIN_TIMEOUT_SECS},
+    infrastructure::credentials::Credentials,
+};
+use anyhow::{Context, Result, bail};
+use base64::prelude::*;
+use rand::{Rng, distributions::Alphanumeric};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+const CODE_VERIFIER_LENGTH: usize = 64;
+const STATE_LENGTH: usize = 16;
+
+pub async fn run(config: &SpotifyConfig) -> Result<()> {
+    let redirect_uri = Url::parse(&config.redirect_uri)
+        .with_context(|| format!("リダイレクトURIが不正です: {}", config.redirect_uri))?;
+    let address = format!(
+        "{}:{}",
+        redirect_uri.host_str().unwrap_or_default(),
+        redirect_uri.port_or_known_default().unwrap_or_default()
+    );
+    let listener = TcpListener::bind(&address)
+        .await
+        .with_context(|| format!("{} で待ち受けできませんでした", address))?;
+
+    let code_verifier = random_string(CODE_VERIFIER_LENGTH);
+    let state = random_string(STATE_LENGTH);
+    let authorize_url = Url::parse_with_params(
+        &format!("{}/authorize", config.accounts_base_url),
+        &[
+            ("client_id", config.client_id.as_str()),
+            ("response_type", "code"),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("scope", &AUTHORIZATION_SCOPES.join(" ")),
+            ("state", &state),
+            ("code_challenge_method", "S256"),
+            ("code_challenge", &code_challenge(&code_verifier)),
+        ],
+    )?;
+    println!(
+        "以下のURLをブラウザで開き、Spotifyへのアクセスを許可してください:\n\n{}\n",
+        authorize_url
+    );
+
+    let code = timeout(
+        Duration::from_secs(LOGIN_TIMEOUT_SECS),
+        wait_for_code(&listener, redirect_uri.path(), &state),
+    )
+    .await
+    .context("ログインがタイムアウトしました")??;
+    let response = token::post_authorization_code(
+        &HttpClient::new(config)?,
+        config,
+        &AuthorizationCodeParams {
+            code: &code,
+            redirect_uri: &config.redirect_uri,
+            code_verifier: &code_verifier,
+        },
+    )
+    .await
+    .context("認可コードをトークンに交換できませんでした")?;
+    let refresh_token = response
+        .refresh_token
+        .context("レスポンスにリフレッシュトークンが含まれていません")?;
+    Credentials::new(refresh_token, response.scope, true).save(&config.credentials_path)?;
+    println!(
+        "ログインしました。リフレッシュトークンを保存しました: {}",
+        config.credentials_path.display()
+    );
+
+    Ok(())
+}
+
+fn random_string(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+async fn wait_for_code(listener: &TcpListener, callback_path: &str, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let Some(target) = read_request_target(&mut stream).await? else {
+            continue;
+        };
+        let url = Url::parse(&format!("http://localhost{}", target))?;
+        // favicon などコールバック以外のリクエストは無視する
+        if url.path() != callback_path {
+            respond(&mut stream, "404 Not Found", "Not Found").await?;
+            continue;
+        }
+
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.into_owned())
+        };
+        if param("state").as_deref() != Some(state) {
+            respond(&mut stream, "400 Bad Request", "state が一致しません").await?;
+            bail!("state が一致しません");
+        }
+        if let Some(error) = param("error") {
+            respond(&mut stream, "400 Bad Request", "ログインが拒否されました").await?;
+            bail!("ログインが拒否されました: {}", error);
+        }
+        let code = param("code").context("認可コードが含まれていません")?;
+        respond(
+            &mut stream,
+            "200 OK",
+            "ログインしました。このウィンドウを閉じてください。",
+        )
+        .await?;
+
+        return Ok(code);
+    }
+}
+
+async fn read_request_target(stream: &mut TcpStream) -> Result<Option<String>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let request = String::from_utf8_lossy(&buffer);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+
+    Ok(match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    })
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{
+    config::DatabaseConfig,
+    infrastructure::{database::DatabasePool, migration::MigrationState},
+};
+use anyhow::Result;
+
+pub async fn run(config: &DatabaseConfig, status: bool) -> Result<()> {
+    let pool = DatabasePool::connect(config).await?;
+    if !status {
+        pool.migrate().await?;
+        println!("マイグレーションを適用しました");
+    }
+
+    let statuses = pool.migration_status().await?;
+    for status in &statuses {
+        let name = format!("{} {}", status.version, status.description);
+        println!("{} ({})", name.trim_end(), status.state.description());
+    }
+    let pending = statuses
+        .iter()
+        .filter(|status| status.state == MigrationState::Pending)
+        .count();
+    if status && pending > 0 {
+        println!(
+            "\n未適用のマイグレーションが{}件あります。spotify-mcp migrate で適用してください",
+            pending
+        );
+    }
+
+    Ok(())
+}
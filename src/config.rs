@@ -0,0 +1,389 @@
+use crate::constant::spotify::{
+    ACCOUNTS_BASE_URL, API_BASE_URL, DEFAULT_BATCH_CONCURRENCY, DEFAULT_MAX_RETRIES,
+    DEFAULT_RATE_LIMIT_BURST, DEFAULT_RATE_LIMIT_PER_SECOND, DEFAULT_RETRY_INITIAL_BACKOFF_MS,
+    DEFAULT_RETRY_MAX_BACKOFF_MS, DEFAULT_TIMEOUT_SECS, DEFAULT_USER_AGENT,
+};
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::{env, fs, path::PathBuf, str::FromStr, time::Duration};
+
+const CONFIG_PATH_ENV: &str = "SPOTIFY_MCP_CONFIG";
+const DEFAULT_CONFIG_PATH: &str = "spotify-mcp.toml";
+const DEFAULT_DATABASE_MAX_CONNECTIONS: u32 = 5;
+const DEFAULT_REDIRECT_URI: &str = "http://127.0.0.1:8888/callback";
+const CREDENTIALS_FILE_NAME: &str = "credentials.json";
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    #[serde(default)]
+    spotify: FileSpotifyConfig,
+    #[serde(default)]
+    database: FileDatabaseConfig,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileSpotifyConfig {
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    refresh_token: Option<String>,
+    credentials_path: Option<String>,
+    redirect_uri: Option<String>,
+    api_base_url: Option<String>,
+    accounts_base_url: Option<String>,
+    timeout_secs: Option<u64>,
+    user_agent: Option<String>,
+    batch_concurrency: Option<usize>,
+    preferred_device: Option<String>,
+    #[serde(default)]
+    retry: FileRetryConfig,
+    #[serde(default)]
+    rate_limit: FileRateLimitConfig,
+    #[serde(default)]
+    cassette: FileCassetteConfig,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileRetryConfig {
+    max_retries: Option<u32>,
+    initial_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileRateLimitConfig {
+    burst: Option<u32>,
+    requests_per_second: Option<f64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileCassetteConfig {
+    mode: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileDatabaseConfig {
+    url: Option<String>,
+    max_connections: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub spotify: SpotifyConfig,
+    pub database: DatabaseConfig,
+}
+
+#[derive(Clone)]
+pub struct SpotifyConfig {
+    pub client_id: String,
+    // PKCEでログインした場合は不要
+    pub client_secret: Option<String>,
+    // `login` で保存した認証情報ファイルがあればそちらを優先する
+    pub refresh_token: Option<String>,
+    pub credentials_path: PathBuf,
+    pub redirect_uri: String,
+    // テスト時はローカルのモックサーバーに向けられる
+    pub api_base_url: String,
+    pub accounts_base_url: String,
+    pub timeout: Duration,
+    pub user_agent: String,
+    // ID上限のあるエンドポイントを分割して呼ぶときの同時実行数
+    pub batch_concurrency: usize,
+    // アクティブなデバイスがないときに優先して再生するデバイスの名前またはID
+    pub preferred_device: Option<String>,
+    pub retry: RetryConfig,
+    pub rate_limit: RateLimitConfig,
+    // テスト用に Spotify API とのやり取りを記録・再生する
+    pub cassette: Option<CassetteConfig>,
+}
+
+#[derive(Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    // Retry-After がこれより長い場合は待たずにエラーにする
+    pub max_backoff: Duration,
+}
+
+#[derive(Clone)]
+pub struct RateLimitConfig {
+    pub burst: u32,
+    pub requests_per_second: f64,
+}
+
+#[derive(Clone)]
+pub struct CassetteConfig {
+    pub mode: CassetteMode,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    // 実際の API を呼び、やり取りをファイルに書き出す
+    Record,
+    // ファイルに記録したレスポンスを返し、API は呼ばない
+    Replay,
+}
+
+#[derive(Clone)]
+pub struct DatabaseConfig {
+    pub backend: DatabaseBackend,
+    pub url: String,
+    pub max_connections: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseBackend {
+    MySql,
+    Sqlite,
+}
+
+impl Config {
+    // 環境変数 > 設定ファイル の優先順で読み込む
+    pub fn load() -> Result<Self> {
+        let file = load_file()?;
+
+        Ok(Self {
+            spotify: SpotifyConfig::from_file(file.spotify)?,
+            database: DatabaseConfig::from_file(file.database)?,
+        })
+    }
+}
+
+impl SpotifyConfig {
+    pub fn load() -> Result<Self> {
+        Self::from_file(load_file()?.spotify)
+    }
+
+    fn from_file(file: FileSpotifyConfig) -> Result<Self> {
+        let client_id = require(
+            resolve("SPOTIFY_CLIENT_ID", file.client_id),
+            "SPOTIFY_CLIENT_ID (spotify.client_id)",
+        )?;
+        let redirect_uri = resolve("SPOTIFY_REDIRECT_URI", file.redirect_uri)
+            .unwrap_or_else(|| DEFAULT_REDIRECT_URI.to_string());
+        if !redirect_uri.starts_with("http://127.0.0.1:")
+            && !redirect_uri.starts_with("http://[::1]:")
+        {
+            bail!(
+                "SPOTIFY_REDIRECT_URI はループバックアドレス (http://127.0.0.1:<ポート>/... または http://[::1]:<ポート>/...) で指定してください: {}",
+                redirect_uri
+            );
+        }
+        let credentials_path = match resolve("SPOTIFY_CREDENTIALS_PATH", file.credentials_path) {
+            Some(path) => PathBuf::from(path),
+            None => default_credentials_path(),
+        };
+        let timeout_secs = parse_env("SPOTIFY_TIMEOUT_SECS")?
+            .or(file.timeout_secs)
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        if timeout_secs == 0 {
+            bail!("SPOTIFY_TIMEOUT_SECS は1以上で指定してください");
+        }
+        let batch_concurrency = parse_env("SPOTIFY_BATCH_CONCURRENCY")?
+            .or(file.batch_concurrency)
+            .unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+        if batch_concurrency == 0 {
+            bail!("SPOTIFY_BATCH_CONCURRENCY は1以上で指定してください");
+        }
+
+        Ok(Self {
+            client_id,
+            client_secret: resolve("SPOTIFY_CLIENT_SECRET", file.client_secret),
+            refresh_token: resolve("SPOTIFY_REFRESH_TOKEN", file.refresh_token),
+            credentials_path,
+            redirect_uri,
+            api_base_url: base_url("SPOTIFY_API_BASE_URL", file.api_base_url, API_BASE_URL)?,
+            accounts_base_url: base_url(
+                "SPOTIFY_ACCOUNTS_BASE_URL",
+                file.accounts_base_url,
+                ACCOUNTS_BASE_URL,
+            )?,
+            timeout: Duration::from_secs(timeout_secs),
+            user_agent: resolve("SPOTIFY_USER_AGENT", file.user_agent)
+                .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            batch_concurrency,
+            preferred_device: resolve("SPOTIFY_PREFERRED_DEVICE", file.preferred_device),
+            retry: RetryConfig::from_file(file.retry)?,
+            rate_limit: RateLimitConfig::from_file(file.rate_limit)?,
+            cassette: CassetteConfig::from_file(file.cassette)?,
+        })
+    }
+}
+
+impl RetryConfig {
+    fn from_file(file: FileRetryConfig) -> Result<Self> {
+        let max_retries = parse_env("SPOTIFY_MAX_RETRIES")?
+            .or(file.max_retries)
+            .unwrap_or(DEFAULT_MAX_RETRIES);
+        let initial_backoff_ms = parse_env("SPOTIFY_RETRY_INITIAL_BACKOFF_MS")?
+            .or(file.initial_backoff_ms)
+            .unwrap_or(DEFAULT_RETRY_INITIAL_BACKOFF_MS);
+        let max_backoff_ms = parse_env("SPOTIFY_RETRY_MAX_BACKOFF_MS")?
+            .or(file.max_backoff_ms)
+            .unwrap_or(DEFAULT_RETRY_MAX_BACKOFF_MS);
+        if initial_backoff_ms == 0 || initial_backoff_ms > max_backoff_ms {
+            bail!(
+                "SPOTIFY_RETRY_INITIAL_BACKOFF_MS は1以上かつ SPOTIFY_RETRY_MAX_BACKOFF_MS 以下で指定してください"
+            );
+        }
+
+        Ok(Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(initial_backoff_ms),
+            max_backoff: Duration::from_millis(max_backoff_ms),
+        })
+    }
+}
+
+impl RateLimitConfig {
+    fn from_file(file: FileRateLimitConfig) -> Result<Self> {
+        let burst = parse_env("SPOTIFY_RATE_LIMIT_BURST")?
+            .or(file.burst)
+            .unwrap_or(DEFAULT_RATE_LIMIT_BURST);
+        let requests_per_second = parse_env("SPOTIFY_RATE_LIMIT_PER_SECOND")?
+            .or(file.requests_per_second)
+            .unwrap_or(DEFAULT_RATE_LIMIT_PER_SECOND);
+        if burst == 0 {
+            bail!("SPOTIFY_RATE_LIMIT_BURST は1以上で指定してください");
+        }
+        if !(requests_per_second.is_finite() && requests_per_second > 0.0) {
+            bail!("SPOTIFY_RATE_LIMIT_PER_SECOND は正の数で指定してください");
+        }
+
+        Ok(Self {
+            burst,
+            requests_per_second,
+        })
+    }
+}
+
+impl CassetteConfig {
+    fn from_file(file: FileCassetteConfig) -> Result<Option<Self>> {
+        let Some(mode) = resolve("SPOTIFY_CASSETTE_MODE", file.mode) else {
+            return Ok(None);
+        };
+        let mode = match mode.as_str() {
+            "record" => CassetteMode::Record,
+            "replay" => CassetteMode::Replay,
+            _ => bail!(
+                "SPOTIFY_CASSETTE_MODE は record または replay で指定してください: {}",
+                mode
+            ),
+        };
+        let path = require(
+            resolve("SPOTIFY_CASSETTE_PATH", file.path),
+            "SPOTIFY_CASSETTE_PATH (spotify.cassette.path)",
+        )?;
+
+        Ok(Some(Self {
+            mode,
+            path: PathBuf::from(path),
+        }))
+    }
+}
+
+impl DatabaseConfig {
+    pub fn load() -> Result<Self> {
+        Self::from_file(load_file()?.database)
+    }
+
+    fn from_file(file: FileDatabaseConfig) -> Result<Self> {
+        let url = require(
+            resolve("DATABASE_URL", file.url),
+            "DATABASE_URL (database.url)",
+        )?;
+        // 接続先は URL のスキームで切り替える
+        let backend = if url.starts_with("mysql://") {
+            DatabaseBackend::MySql
+        } else if url.starts_with("sqlite:") {
+            DatabaseBackend::Sqlite
+        } else {
+            bail!("DATABASE_URL は mysql:// または sqlite: で始まる必要があります");
+        };
+        let max_connections = parse_env("DATABASE_MAX_CONNECTIONS")?
+            .or(file.max_connections)
+            .unwrap_or(DEFAULT_DATABASE_MAX_CONNECTIONS);
+        if max_connections == 0 {
+            bail!("DATABASE_MAX_CONNECTIONS は1以上で指定してください");
+        }
+
+        Ok(Self {
+            backend,
+            url,
+            max_connections,
+        })
+    }
+}
+
+fn env_value(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|value| !value.trim().is_empty())
+}
+
+fn resolve(env_key: &str, file_value: Option<String>) -> Option<String> {
+    env_value(env_key).or(file_value.filter(|value| !value.trim().is_empty()))
+}
+
+fn parse_env<T: FromStr>(key: &str) -> Result<Option<T>> {
+    env_value(key)
+        .map(|value| {
+            value
+                .parse::<T>()
+                .ok()
+                .with_context(|| format!("{} は数値で指定してください: {}", key, value))
+        })
+        .transpose()
+}
+
+fn base_url(env_key: &str, file_value: Option<String>, default: &str) -> Result<String> {
+    let url = resolve(env_key, file_value).unwrap_or_else(|| default.to_string());
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        bail!("{} は http(s):// で始まる必要があります: {}", env_key, url);
+    }
+
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+fn require(value: Option<String>, name: &str) -> Result<String> {
+    value.with_context(|| {
+        format!(
+            "必要な設定がありません。環境変数または設定ファイルで指定してください: {}",
+            name
+        )
+    })
+}
+
+fn default_credentials_path() -> PathBuf {
+    let config_dir = env_value("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env_value("HOME").map(|home| PathBuf::from(home).join(".config")));
+    match config_dir {
+        Some(config_dir) => config_dir.join("spotify-mcp").join(CREDENTIALS_FILE_NAME),
+        None => PathBuf::from(CREDENTIALS_FILE_NAME),
+    }
+}
+
+fn load_file() -> Result<FileConfig> {
+    let _ = dotenvy::dotenv();
+    let (path, required) = match env_value(CONFIG_PATH_ENV) {
+        Some(path) => (PathBuf::from(path), true),
+        None => (PathBuf::from(DEFAULT_CONFIG_PATH), false),
+    };
+    if !path.exists() {
+        if required {
+            bail!("設定ファイルが見つかりません: {}", path.display());
+        }
+        return Ok(FileConfig::default());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("設定ファイルの読み込みに失敗しました: {}", path.display()))?;
+
+    toml::from_str(&content)
+        .with_context(|| format!("設定ファイルの形式が不正です: {}", path.display()))
+}
@@ -1,2 +1,5 @@
+pub mod database;
+pub mod excluded_artist;
+pub mod music_genre;
 pub mod music_search;
 pub mod spotify;
@@ -0,0 +1 @@
+pub const IN_QUERY_CHUNK_SIZE: usize = 1000;
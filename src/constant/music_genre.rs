@@ -0,0 +1,2 @@
+pub const DEFAULT_HARVEST_SEARCH_LIMIT: u32 = 50;
+pub const MAX_HARVEST_SEARCH_LIMIT: u32 = 1000;
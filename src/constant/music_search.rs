@@ -1,2 +1,6 @@
 pub const INITIAL_POSITION: u32 = 0;
 pub const FETCH_LIMIT: u32 = 10;
+pub const DEFAULT_DISCOVER_COUNT: u32 = 10;
+pub const MAX_DISCOVER_COUNT: u32 = 50;
+// 候補が見つからない場合に1回の呼び出しで走査するアーティスト数の上限
+pub const MAX_DISCOVER_SCAN: u32 = 500;
@@ -1 +1,26 @@
 pub const API_BASE_URL: &str = "https://api.spotify.com";
+pub const ACCOUNTS_BASE_URL: &str = "https://accounts.spotify.com";
+pub const MAX_PAGE_LIMIT: u32 = 50;
+pub const SEARCH_OFFSET_LIMIT: u32 = 1000;
+pub const FOLLOWING_IDS_LIMIT: usize = 50;
+pub const ARTISTS_IDS_LIMIT: usize = 50;
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_USER_AGENT: &str = concat!("spotify-mcp/", env!("CARGO_PKG_VERSION"));
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_RETRY_INITIAL_BACKOFF_MS: u64 = 500;
+pub const DEFAULT_RETRY_MAX_BACKOFF_MS: u64 = 30_000;
+pub const DEFAULT_RATE_LIMIT_BURST: u32 = 10;
+pub const DEFAULT_RATE_LIMIT_PER_SECOND: f64 = 5.0;
+pub const ACCESS_TOKEN_REFRESH_MARGIN_SECS: u64 = 60;
+pub const ACCESS_TOKEN_RETRY_INTERVAL_SECS: u64 = 30;
+pub const AUTHORIZATION_SCOPES: &[&str] = &[
+    "user-follow-read",
+    "user-follow-modify",
+    "user-read-playback-state",
+    "user-modify-playback-state",
+    "playlist-modify-public",
+    "playlist-modify-private",
+];
+pub const LOGIN_TIMEOUT_SECS: u64 = 300;
+pub const MAX_VOLUME_PERCENT: u32 = 100;
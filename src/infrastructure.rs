@@ -1 +1,3 @@
+pub mod credentials;
 pub mod database;
+pub mod migration;
@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Credentials {
+    pub refresh_token: String,
+    pub scope: Option<String>,
+    // PKCE (公開クライアント) で発行されたトークンか
+    #[serde(default)]
+    pub pkce: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Credentials {
+    pub fn new(refresh_token: String, scope: Option<String>, pkce: bool) -> Self {
+        Self {
+            refresh_token,
+            scope,
+            pkce,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path).with_context(|| {
+            format!(
+                "認証情報ファイルの読み込みに失敗しました: {}",
+                path.display()
+            )
+        })?;
+        let credentials = serde_json::from_str(&content)
+            .with_context(|| format!("認証情報ファイルの形式が不正です: {}", path.display()))?;
+
+        Ok(Some(credentials))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        // リフレッシュトークンを他のユーザーに読まれないよう、0600 で作った一時ファイルに書いてから置き換える
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        let write = || -> Result<()> {
+            // 前回の書き込みで残った一時ファイルは権限が違う可能性があるため、消してから作り直す
+            if temp_path.exists() {
+                fs::remove_file(&temp_path)?;
+            }
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut file = options.open(&temp_path)?;
+            file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temp_path, path)?;
+
+            Ok(())
+        };
+        write().with_context(|| {
+            format!(
+                "認証情報ファイルの書き込みに失敗しました: {}",
+                path.display()
+            )
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,12 +1,70 @@
+use crate::{
+    config::{DatabaseBackend, DatabaseConfig},
+    infrastructure::migration::{self, MYSQL_MIGRATOR, MigrationStatus, SQLITE_MIGRATOR},
+    repository::{Repositories, mysql::MySqlRepository, sqlite::SqliteRepository},
+};
 use anyhow::Result;
-use sqlx::{MySql, Pool, mysql::MySqlPoolOptions};
+use sqlx::{
+    MySql, Pool, Sqlite,
+    mysql::MySqlPoolOptions,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use std::str::FromStr;
 
-pub async fn get_pool() -> Result<Pool<MySql>> {
-    let database_url = env!("DATABASE_URL");
+pub async fn get_pool(config: &DatabaseConfig) -> Result<Pool<MySql>> {
     let pool = MySqlPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
+        .max_connections(config.max_connections)
+        .connect(&config.url)
         .await?;
 
     Ok(pool)
 }
+
+pub async fn get_sqlite_pool(config: &DatabaseConfig) -> Result<Pool<Sqlite>> {
+    let options = SqliteConnectOptions::from_str(&config.url)?
+        .create_if_missing(true)
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(config.max_connections)
+        .connect_with(options)
+        .await?;
+
+    Ok(pool)
+}
+
+pub enum DatabasePool {
+    MySql(Pool<MySql>),
+    Sqlite(Pool<Sqlite>),
+}
+
+impl DatabasePool {
+    pub async fn connect(config: &DatabaseConfig) -> Result<Self> {
+        let pool = match config.backend {
+            DatabaseBackend::MySql => Self::MySql(get_pool(config).await?),
+            DatabaseBackend::Sqlite => Self::Sqlite(get_sqlite_pool(config).await?),
+        };
+
+        Ok(pool)
+    }
+
+    pub async fn migrate(&self) -> Result<()> {
+        match self {
+            Self::MySql(pool) => migration::run(pool, &MYSQL_MIGRATOR).await,
+            Self::Sqlite(pool) => migration::run(pool, &SQLITE_MIGRATOR).await,
+        }
+    }
+
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        match self {
+            Self::MySql(pool) => migration::status(pool, &MYSQL_MIGRATOR).await,
+            Self::Sqlite(pool) => migration::status(pool, &SQLITE_MIGRATOR).await,
+        }
+    }
+
+    pub fn into_repositories(self) -> Repositories {
+        match self {
+            Self::MySql(pool) => Repositories::new(MySqlRepository::new(pool)),
+            Self::Sqlite(pool) => Repositories::new(SqliteRepository::new(pool)),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{Result, bail};
+use sqlx::{
+    Acquire, Database, Pool,
+    migrate::{Migrate, Migrator},
+};
+use std::ops::DerefMut;
+
+// マイグレーションはビルド時にバイナリへ埋め込む
+pub static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    // 適用後にファイルの内容が変わっている
+    Modified,
+    // データベースには適用済みだが、このバイナリには含まれていない
+    Unknown,
+}
+
+impl MigrationState {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Applied => "適用済み",
+            Self::Pending => "未適用",
+            Self::Modified => "適用後に変更されています",
+            Self::Unknown => "このバイナリに含まれていません",
+        }
+    }
+}
+
+pub async fn status<DB>(pool: &Pool<DB>, migrator: &Migrator) -> Result<Vec<MigrationStatus>>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    let mut connection = pool.acquire().await?;
+    let connection = connection.deref_mut();
+    connection.ensure_migrations_table().await?;
+    let applied = connection.list_applied_migrations().await?;
+
+    let mut statuses = migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| {
+            let state = match applied
+                .iter()
+                .find(|applied| applied.version == migration.version)
+            {
+                Some(applied) if applied.checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+                None => MigrationState::Pending,
+            };
+
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect::<Vec<_>>();
+    for applied in &applied {
+        if !migrator
+            .iter()
+            .any(|migration| migration.version == applied.version)
+        {
+            statuses.push(MigrationStatus {
+                version: applied.version,
+                description: String::new(),
+                state: MigrationState::Unknown,
+            });
+        }
+    }
+    statuses.sort_by_key(|status| status.version);
+
+    Ok(statuses)
+}
+
+pub async fn run<'a, DB>(pool: &'a Pool<DB>, migrator: &Migrator) -> Result<()>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+    &'a Pool<DB>: Acquire<'a, Database = DB>,
+{
+    let statuses = status(pool, migrator).await?;
+    let unknown = versions(&statuses, MigrationState::Unknown);
+    if !unknown.is_empty() {
+        bail!(
+            "データベースのスキーマがこのバイナリより新しいです (未知のマイグレーション: {})。spotify-mcp を更新してください",
+            unknown
+        );
+    }
+    let modified = versions(&statuses, MigrationState::Modified);
+    if !modified.is_empty() {
+        bail!(
+            "適用済みのマイグレーションの内容が変わっています (バージョン: {})",
+            modified
+        );
+    }
+    migrator.run(pool).await?;
+
+    Ok(())
+}
+
+fn versions(statuses: &[MigrationStatus], state: MigrationState) -> String {
+    statuses
+        .iter()
+        .filter(|status| status.state == state)
+        .map(|status| status.version.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,4 +1,8 @@
 pub mod client;
+pub mod command;
+pub mod config;
 pub mod constant;
 pub mod infrastructure;
 pub mod model;
+pub mod repository;
+pub mod server;
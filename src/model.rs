@@ -1,3 +1,5 @@
 pub mod excluded_artist;
+pub mod exclusion_category;
 pub mod music_genre;
 pub mod music_search_progress;
+pub mod sort_order;
@@ -1,81 +1,351 @@
+use crate::{
+    constant::database::IN_QUERY_CHUNK_SIZE,
+    model::{exclusion_category::ExclusionCategory, sort_order::SortOrder},
+};
 use anyhow::Result;
 use chrono::NaiveDateTime;
-use sqlx::MySqlPool;
+use sqlx::{MySql, MySqlConnection, MySqlPool, QueryBuilder};
+use std::collections::HashSet;
 
 pub struct InsertInput {
-    id: String,
-    name: String,
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) reason: Option<String>,
+    pub(crate) category: Option<ExclusionCategory>,
+    pub(crate) expires_at: Option<NaiveDateTime>,
+    pub(crate) source_music_genre_id: Option<u32>,
 }
 
 impl InsertInput {
-    pub fn new(id: String, name: String) -> Self {
-        Self { id, name }
+    pub fn new(
+        id: String,
+        name: String,
+        reason: Option<String>,
+        category: Option<ExclusionCategory>,
+        expires_at: Option<NaiveDateTime>,
+        source_music_genre_id: Option<u32>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            reason,
+            category,
+            expires_at,
+            source_music_genre_id,
+        }
     }
 }
 
+pub struct ListInput {
+    pub(crate) limit: u32,
+    pub(crate) offset: u32,
+    pub(crate) order: SortOrder,
+}
+
+impl ListInput {
+    pub fn new(limit: u32, offset: u32, order: SortOrder) -> Self {
+        Self {
+            limit,
+            offset,
+            order,
+        }
+    }
+}
+
+#[derive(Clone, sqlx::FromRow)]
 pub struct ExcludedArtist {
     pub id: String,
     pub name: String,
+    pub reason: Option<String>,
+    pub category: Option<ExclusionCategory>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub source_music_genre_id: Option<u32>,
     pub created_at: NaiveDateTime,
 }
 
+// 除外の有効期限は UTC で保存するため、セッションのタイムゾーンに依らない UTC_TIMESTAMP() と比較する
 impl ExcludedArtist {
     pub async fn find_by_ids(db_pool: &MySqlPool, ids: &[String]) -> Result<Vec<Self>> {
-        if ids.is_empty() {
-            return Ok(vec![]);
+        let mut excluded_artists = Vec::new();
+        for chunk in ids.chunks(IN_QUERY_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new(
+                r#"
+                  SELECT
+                    id,
+                    name,
+                    reason,
+                    category,
+                    expires_at,
+                    source_music_genre_id,
+                    created_at
+                  FROM
+                    excluded_artists
+                  WHERE
+                    (expires_at IS NULL OR expires_at > UTC_TIMESTAMP())
+                    AND id IN (
+                "#,
+            );
+            let mut separated = query.separated(", ");
+            for id in chunk {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+            excluded_artists.extend(query.build_query_as::<Self>().fetch_all(db_pool).await?);
         }
-        let excluded_artists = sqlx::query_as!(
-            Self,
+        excluded_artists.sort_by(|a, b| b.id.cmp(&a.id));
+
+        Ok(excluded_artists)
+    }
+
+    // 除外期限が切れた行は登録し直し、期限内の行があれば重複エラーにする
+    pub async fn insert(db_pool: &MySqlPool, input: &InsertInput) -> Result<Self> {
+        let mut tx = db_pool.begin().await?;
+        delete_expired(&mut tx, &[input.id.as_str()]).await?;
+        // 主キーは文字列のため last_insert_id は使えず、登録したIDで取得し直す
+        sqlx::query(
+            r#"
+              INSERT INTO
+                excluded_artists (
+                  id,
+                  name,
+                  reason,
+                  category,
+                  expires_at,
+                  source_music_genre_id
+                )
+              VALUES
+                (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&input.id)
+        .bind(&input.name)
+        .bind(&input.reason)
+        .bind(input.category)
+        .bind(input.expires_at)
+        .bind(input.source_music_genre_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let excluded_artist = sqlx::query_as::<_, Self>(
             r#"
               SELECT
                 id,
                 name,
+                reason,
+                category,
+                expires_at,
+                source_music_genre_id,
                 created_at
               FROM
                 excluded_artists
               WHERE
-                id IN ( ? )
-              ORDER BY
-                id DESC
+                id = ?
             "#,
-            &ids.join(",")
         )
-        .fetch_all(db_pool)
+        .bind(&input.id)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
+
+        Ok(excluded_artist)
+    }
+
+    // 既に登録済みのIDは無視し、新たに登録した件数を返す。除外期限が切れた行は登録し直す。
+    // sqlx は CLIENT_FOUND_ROWS で接続するため ON DUPLICATE KEY UPDATE の影響行数には登録済みの行も含まれ、
+    // INSERT IGNORE では外部キー違反なども無視されてしまう。そのため登録済みのIDを先に数えて差し引く
+    pub async fn bulk_insert(db_pool: &MySqlPool, inputs: &[InsertInput]) -> Result<u64> {
+        let mut tx = db_pool.begin().await?;
+        let mut inserted = 0;
+        for chunk in inputs.chunks(IN_QUERY_CHUNK_SIZE) {
+            let ids = chunk
+                .iter()
+                .map(|input| input.id.as_str())
+                .collect::<HashSet<_>>();
+            delete_expired(&mut tx, &ids.iter().copied().collect::<Vec<_>>()).await?;
+            let mut query = QueryBuilder::<MySql>::new(
+                r#"
+                  SELECT
+                    id
+                  FROM
+                    excluded_artists
+                  WHERE
+                    id IN (
+                "#,
+            );
+            let mut separated = query.separated(", ");
+            for id in &ids {
+                separated.push_bind(*id);
+            }
+            separated.push_unseparated(") FOR UPDATE");
+            let registered = query
+                .build_query_scalar::<String>()
+                .fetch_all(&mut *tx)
+                .await?
+                .len();
+
+            let mut query = QueryBuilder::<MySql>::new(
+                r#"
+                  INSERT INTO
+                    excluded_artists (
+                      id,
+                      name,
+                      reason,
+                      category,
+                      expires_at,
+                      source_music_genre_id
+                    )
+                "#,
+            );
+            query.push_values(chunk, |mut row, input| {
+                row.push_bind(&input.id)
+                    .push_bind(&input.name)
+                    .push_bind(&input.reason)
+                    .push_bind(input.category)
+                    .push_bind(input.expires_at)
+                    .push_bind(input.source_music_genre_id);
+            });
+            query.push(
+                r#"
+                  ON DUPLICATE KEY UPDATE
+                    id = id
+                "#,
+            );
+            query.build().execute(&mut *tx).await?;
+            inserted += ids.len().saturating_sub(registered) as u64;
+        }
+        tx.commit().await?;
+
+        Ok(inserted)
+    }
+
+    pub async fn find_all(db_pool: &MySqlPool, input: &ListInput) -> Result<Vec<Self>> {
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+              SELECT
+                id,
+                name,
+                reason,
+                category,
+                expires_at,
+                source_music_genre_id,
+                created_at
+              FROM
+                excluded_artists
+              WHERE
+                expires_at IS NULL OR expires_at > UTC_TIMESTAMP()
+              ORDER BY
+                created_at
+            "#,
+        );
+        query
+            .push(input.order.as_sql())
+            .push(", id ")
+            .push(input.order.as_sql())
+            .push(" LIMIT ")
+            .push_bind(input.limit)
+            .push(" OFFSET ")
+            .push_bind(input.offset);
+        let excluded_artists = query.build_query_as::<Self>().fetch_all(db_pool).await?;
 
         Ok(excluded_artists)
     }
 
-    pub async fn insert(db_pool: &MySqlPool, input: &InsertInput) -> Result<Self> {
-        let id = sqlx::query!(
+    pub async fn count(db_pool: &MySqlPool) -> Result<i64> {
+        let count = sqlx::query_scalar(
             r#"
-              INSERT INTO
-                excluded_artists (id, name)
-              VALUES
-                (?, ?)
+              SELECT
+                COUNT(*)
+              FROM
+                excluded_artists
+              WHERE
+                expires_at IS NULL OR expires_at > UTC_TIMESTAMP()
             "#,
-            input.id,
-            input.name
         )
-        .execute(db_pool)
-        .await?
-        .last_insert_id();
+        .fetch_one(db_pool)
+        .await?;
+
+        Ok(count)
+    }
 
-        let excluded_artist = sqlx::query_as!(
-            Self,
+    pub async fn search_by_name(db_pool: &MySqlPool, name: &str, limit: u32) -> Result<Vec<Self>> {
+        let pattern = like_pattern(name);
+        let excluded_artists = sqlx::query_as::<_, Self>(
             r#"
               SELECT
-                id, name, created_at
+                id,
+                name,
+                reason,
+                category,
+                expires_at,
+                source_music_genre_id,
+                created_at
               FROM
                 excluded_artists
               WHERE
-                id = ?
+                (expires_at IS NULL OR expires_at > UTC_TIMESTAMP())
+                AND name LIKE ?
+              ORDER BY
+                name ASC
+              LIMIT ?
             "#,
-            id
         )
-        .fetch_one(db_pool)
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(db_pool)
         .await?;
 
-        Ok(excluded_artist)
+        Ok(excluded_artists)
+    }
+
+    pub async fn delete_by_ids(db_pool: &MySqlPool, ids: &[String]) -> Result<u64> {
+        let mut deleted = 0;
+        for chunk in ids.chunks(IN_QUERY_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new(
+                r#"
+                  DELETE FROM
+                    excluded_artists
+                  WHERE
+                    id IN (
+                "#,
+            );
+            let mut separated = query.separated(", ");
+            for id in chunk {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+            deleted += query.build().execute(db_pool).await?.rows_affected();
+        }
+
+        Ok(deleted)
     }
 }
+
+async fn delete_expired(tx: &mut MySqlConnection, ids: &[&str]) -> Result<()> {
+    let mut query = QueryBuilder::<MySql>::new(
+        r#"
+          DELETE FROM
+            excluded_artists
+          WHERE
+            expires_at <= UTC_TIMESTAMP()
+            AND id IN (
+        "#,
+    );
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(*id);
+    }
+    separated.push_unseparated(")");
+    query.build().execute(tx).await?;
+
+    Ok(())
+}
+
+// 部分一致検索のパターンを作る。ワイルドカードはバックスラッシュでエスケープする
+pub(crate) fn like_pattern(name: &str) -> String {
+    format!(
+        "%{}%",
+        name.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    )
+}
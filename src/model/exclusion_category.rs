@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ExclusionCategory {
+    Disliked,
+    AlreadyKnown,
+    WrongGenre,
+    Explicit,
+    Other,
+}
+
+impl ExclusionCategory {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Disliked => "好みではない",
+            Self::AlreadyKnown => "既に知っている",
+            Self::WrongGenre => "ジャンルが違う",
+            Self::Explicit => "過激な表現を含む",
+            Self::Other => "その他",
+        }
+    }
+}
@@ -1,7 +1,12 @@
+pub mod error;
+
 use anyhow::Result;
 use chrono::NaiveDateTime;
-use sqlx::MySqlPool;
+use error::MusicGenreError;
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+use std::collections::HashSet;
 
+#[derive(Clone, sqlx::FromRow)]
 pub struct MusicGenre {
     pub id: u32,
     pub name: String,
@@ -10,10 +15,20 @@ pub struct MusicGenre {
     pub updated_at: NaiveDateTime,
 }
 
+pub struct InsertInput {
+    pub(crate) name: String,
+    pub(crate) search_key: String,
+}
+
+impl InsertInput {
+    pub fn new(name: String, search_key: String) -> Self {
+        Self { name, search_key }
+    }
+}
+
 impl MusicGenre {
     pub async fn find_all(db_pool: &MySqlPool) -> Result<Vec<Self>> {
-        let genres = sqlx::query_as!(
-            MusicGenre,
+        let genres = sqlx::query_as::<_, Self>(
             r#"
                 SELECT
                   id, name, search_key, created_at, updated_at
@@ -21,11 +36,258 @@ impl MusicGenre {
                   music_genres
                 ORDER BY
                   id ASC
-            "#
+            "#,
         )
         .fetch_all(db_pool)
         .await?;
 
         Ok(genres)
     }
+
+    pub async fn find_by_id(db_pool: &MySqlPool, id: u32) -> Result<Option<Self>> {
+        let genre = sqlx::query_as::<_, Self>(
+            r#"
+                SELECT
+                  id, name, search_key, created_at, updated_at
+                FROM
+                  music_genres
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(genre)
+    }
+
+    pub async fn insert(db_pool: &MySqlPool, input: &InsertInput) -> Result<Self> {
+        validate_search_key(&input.search_key)?;
+        let id = sqlx::query(
+            r#"
+                INSERT INTO
+                  music_genres (name, search_key)
+                VALUES
+                  (?, ?)
+            "#,
+        )
+        .bind(&input.name)
+        .bind(&input.search_key)
+        .execute(db_pool)
+        .await
+        .map_err(|e| unique_violation(e, &input.search_key))?
+        .last_insert_id();
+
+        Self::find_by_id(db_pool, id as u32)
+            .await?
+            .ok_or_else(|| MusicGenreError::NotFound(id as u32).into())
+    }
+
+    // 既に登録済みの検索キーは無視し、新たに登録した件数を返す。
+    // ON DUPLICATE KEY UPDATE の影響行数には登録済みの行も含まれるため、登録済みの検索キーを先に数えて差し引く
+    pub async fn bulk_insert(db_pool: &MySqlPool, inputs: &[InsertInput]) -> Result<u64> {
+        if inputs.is_empty() {
+            return Ok(0);
+        }
+        for input in inputs {
+            validate_search_key(&input.search_key)?;
+        }
+        let search_keys = inputs
+            .iter()
+            .map(|input| input.search_key.as_str())
+            .collect::<HashSet<_>>();
+        let mut tx = db_pool.begin().await?;
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+                SELECT
+                  search_key
+                FROM
+                  music_genres
+                WHERE
+                  search_key IN (
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        for search_key in &search_keys {
+            separated.push_bind(*search_key);
+        }
+        separated.push_unseparated(") FOR UPDATE");
+        let registered = query
+            .build_query_scalar::<String>()
+            .fetch_all(&mut *tx)
+            .await?
+            .len();
+
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+                INSERT INTO
+                  music_genres (name, search_key)
+            "#,
+        );
+        query.push_values(inputs, |mut row, input| {
+            row.push_bind(&input.name).push_bind(&input.search_key);
+        });
+        query.push(
+            r#"
+                ON DUPLICATE KEY UPDATE
+                  id = id
+            "#,
+        );
+        query.build().execute(&mut *tx).await?;
+        tx.commit().await?;
+
+        Ok(search_keys.len().saturating_sub(registered) as u64)
+    }
+
+    pub async fn update_name(db_pool: &MySqlPool, id: u32, name: &str) -> Result<Self> {
+        sqlx::query(
+            r#"
+                UPDATE
+                  music_genres
+                SET
+                  name = ?
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(name)
+        .bind(id)
+        .execute(db_pool)
+        .await?;
+
+        Self::find_by_id(db_pool, id)
+            .await?
+            .ok_or_else(|| MusicGenreError::NotFound(id).into())
+    }
+
+    pub async fn update_search_key(db_pool: &MySqlPool, id: u32, search_key: &str) -> Result<Self> {
+        validate_search_key(search_key)?;
+        sqlx::query(
+            r#"
+                UPDATE
+                  music_genres
+                SET
+                  search_key = ?
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(search_key)
+        .bind(id)
+        .execute(db_pool)
+        .await
+        .map_err(|e| unique_violation(e, search_key))?;
+
+        Self::find_by_id(db_pool, id)
+            .await?
+            .ok_or_else(|| MusicGenreError::NotFound(id).into())
+    }
+
+    // cascade が false の場合、音楽検索の進捗から参照されていれば削除しない
+    pub async fn delete(db_pool: &MySqlPool, id: u32, cascade: bool) -> Result<()> {
+        let mut tx = db_pool.begin().await?;
+        let genre: Option<u32> = sqlx::query_scalar(
+            r#"
+                SELECT
+                  id
+                FROM
+                  music_genres
+                WHERE
+                  id = ?
+                FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if genre.is_none() {
+            return Err(MusicGenreError::NotFound(id).into());
+        }
+
+        if cascade {
+            sqlx::query(
+                r#"
+                    DELETE FROM
+                      music_search_progresses
+                    WHERE
+                      music_genre_id = ?
+                "#,
+            )
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            let referenced: i64 = sqlx::query_scalar(
+                r#"
+                    SELECT
+                      COUNT(*)
+                    FROM
+                      music_search_progresses
+                    WHERE
+                      music_genre_id = ?
+                "#,
+            )
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if referenced > 0 {
+                return Err(MusicGenreError::Referenced(id).into());
+            }
+        }
+
+        sqlx::query(
+            r#"
+                DELETE FROM
+                  music_genres
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+// Spotify のジャンルは小文字の単語をスペースやハイフンでつないだもの (例: "j-pop", "hip hop", "r&b")
+pub fn validate_search_key(search_key: &str) -> Result<(), MusicGenreError> {
+    let invalid = |reason| MusicGenreError::InvalidSearchKey {
+        search_key: search_key.to_string(),
+        reason,
+    };
+    if search_key.is_empty() {
+        return Err(invalid("空にはできません"));
+    }
+    if search_key.chars().count() > 100 {
+        return Err(invalid("100文字以内で指定してください"));
+    }
+    if search_key != search_key.trim() || search_key.contains("  ") {
+        return Err(invalid("前後や連続した空白は使えません"));
+    }
+    if search_key.chars().any(char::is_uppercase) {
+        return Err(invalid("小文字で指定してください"));
+    }
+    if !search_key
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '&' | '\'' | '.' | '+'))
+    {
+        return Err(invalid(
+            "英数字・空白・ハイフン・&・'・.・+ 以外の文字は使えません",
+        ));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn unique_violation(e: sqlx::Error, search_key: &str) -> anyhow::Error {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            MusicGenreError::DuplicateSearchKey(search_key.to_string()).into()
+        }
+        _ => e.into(),
+    }
 }
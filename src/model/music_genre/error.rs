@@ -0,0 +1,16 @@
+#[derive(Debug, thiserror::Error)]
+pub enum MusicGenreError {
+    #[error("音楽ジャンルが見つかりません (ID: {0})")]
+    NotFound(u32),
+    #[error("検索キー '{0}' は既に登録されています")]
+    DuplicateSearchKey(String),
+    #[error("検索キー '{search_key}' は Spotify のジャンルとして正しくありません: {reason}")]
+    InvalidSearchKey {
+        search_key: String,
+        reason: &'static str,
+    },
+    #[error(
+        "音楽ジャンル (ID: {0}) は音楽検索の進捗から参照されているため削除できません。進捗ごと削除する場合は cascade を指定してください"
+    )]
+    Referenced(u32),
+}
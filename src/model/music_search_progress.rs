@@ -1,11 +1,15 @@
-use anyhow::Result;
+use crate::constant::{music_search::INITIAL_POSITION, spotify::SEARCH_OFFSET_LIMIT};
+use anyhow::{Result, anyhow};
 use chrono::NaiveDateTime;
 use sqlx::MySqlPool;
 
+#[derive(Clone, sqlx::FromRow)]
 pub struct MusicSearchProgress {
     pub id: u32,
     pub music_genre_id: u32,
     pub position: u32,
+    pub last_total: Option<u32>,
+    pub completed_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -21,65 +25,218 @@ impl UpsertInput {
 }
 
 impl MusicSearchProgress {
+    // 検索APIは offset の上限を超えて取得できないため、総数が多くても上限までで終わりとする
+    pub fn reachable_total(&self) -> Option<u32> {
+        self.last_total.map(|total| total.min(SEARCH_OFFSET_LIMIT))
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    pub fn percent_complete(&self) -> Option<f64> {
+        if self.is_completed() {
+            return Some(100.0);
+        }
+        match self.reachable_total()? {
+            0 => Some(100.0),
+            total => Some((self.position.min(total) as f64 / total as f64) * 100.0),
+        }
+    }
+
     pub async fn find_by_music_genre_id(
         db_pool: &MySqlPool,
         music_genre_id: u32,
     ) -> Result<Option<Self>> {
-        let result = sqlx::query_as!(
-            MusicSearchProgress,
+        let result = sqlx::query_as::<_, Self>(
             r#"
                 SELECT
-                  id, music_genre_id, position, created_at, updated_at
+                  id, music_genre_id, position, last_total, completed_at, created_at, updated_at
                 FROM
                   music_search_progresses
                 WHERE
                   music_genre_id = ?
             "#,
-            music_genre_id
         )
+        .bind(music_genre_id)
         .fetch_optional(db_pool)
         .await?;
 
         Ok(result)
     }
 
+    // 進捗がなければ初期位置で作成してから返す
+    pub async fn find_or_create(db_pool: &MySqlPool, music_genre_id: u32) -> Result<Self> {
+        sqlx::query(
+            r#"
+                INSERT INTO
+                  music_search_progresses (music_genre_id, position)
+                VALUES
+                  (?, ?)
+                ON DUPLICATE KEY UPDATE
+                  id = id
+            "#,
+        )
+        .bind(music_genre_id)
+        .bind(INITIAL_POSITION)
+        .execute(db_pool)
+        .await?;
+
+        let music_search_progress = Self::find_by_music_genre_id(db_pool, music_genre_id)
+            .await?
+            .ok_or_else(|| anyhow!("音楽検索の進捗が見つかりません"))?;
+
+        Ok(music_search_progress)
+    }
+
+    // 1つの UPDATE で consumed 件だけ進めるため、同時に呼ばれても進めた分が失われない。
+    // 検索結果の総数や offset の上限を超えては進めない。
+    // expected_position を指定した場合は位置が一致するときだけ進め、一致しなければ None を返す
+    pub async fn advance(
+        db_pool: &MySqlPool,
+        music_genre_id: u32,
+        expected_position: Option<u32>,
+        consumed: u32,
+    ) -> Result<Option<Self>> {
+        // SET は左から順に評価されるため、completed_at の判定には更新後の position が使われる
+        let updated = sqlx::query(
+            r#"
+                UPDATE
+                  music_search_progresses
+                SET
+                  position = LEAST(
+                    position + ?,
+                    GREATEST(position, LEAST(COALESCE(last_total, ?), ?))
+                  ),
+                  completed_at = CASE
+                    WHEN position >= LEAST(COALESCE(last_total, ?), ?) THEN COALESCE(completed_at, CURRENT_TIMESTAMP)
+                    ELSE NULL
+                  END
+                WHERE
+                  music_genre_id = ?
+                  AND (? IS NULL OR position = ?)
+            "#,
+        )
+        .bind(consumed)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(music_genre_id)
+        .bind(expected_position)
+        .bind(expected_position)
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+        // CLIENT_FOUND_ROWS で接続しているため、値が変わらなくても条件に一致すれば1になる
+        if updated == 0 {
+            return Ok(None);
+        }
+
+        Self::find_by_music_genre_id(db_pool, music_genre_id).await
+    }
+
+    pub async fn record_total(db_pool: &MySqlPool, music_genre_id: u32, total: u32) -> Result<()> {
+        sqlx::query(
+            r#"
+                UPDATE
+                  music_search_progresses
+                SET
+                  last_total = ?,
+                  completed_at = CASE
+                    WHEN position >= LEAST(last_total, ?) THEN COALESCE(completed_at, CURRENT_TIMESTAMP)
+                    ELSE NULL
+                  END
+                WHERE
+                  music_genre_id = ?
+            "#,
+        )
+        .bind(total)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(music_genre_id)
+        .execute(db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // 総数に達していなくても検索結果が尽きた場合に使う
+    pub async fn complete(db_pool: &MySqlPool, music_genre_id: u32) -> Result<()> {
+        sqlx::query(
+            r#"
+                UPDATE
+                  music_search_progresses
+                SET
+                  completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP)
+                WHERE
+                  music_genre_id = ?
+            "#,
+        )
+        .bind(music_genre_id)
+        .execute(db_pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn upsert(
         db_pool: &MySqlPool,
         music_genre_id: u32,
         input: &UpsertInput,
     ) -> Result<Self> {
-        let id = sqlx::query!(
+        // 更新になった場合 last_insert_id は登録した行を指さないため、一意な music_genre_id で取得し直す
+        sqlx::query(
             r#"
                 INSERT INTO
                   music_search_progresses (music_genre_id, position)
                 VALUES
                   (?, ?)
                 ON DUPLICATE KEY UPDATE
-                  position = ?
+                  position = ?,
+                  completed_at = CASE
+                    WHEN position >= LEAST(COALESCE(last_total, ?), ?) THEN COALESCE(completed_at, CURRENT_TIMESTAMP)
+                    ELSE NULL
+                  END
             "#,
-            music_genre_id,
-            input.position,
-            input.position
         )
+        .bind(music_genre_id)
+        .bind(input.position)
+        .bind(input.position)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(SEARCH_OFFSET_LIMIT)
         .execute(db_pool)
-        .await?
-        .last_insert_id();
+        .await?;
 
-        let music_search_progress = sqlx::query_as!(
-            MusicSearchProgress,
+        let music_search_progress = Self::find_by_music_genre_id(db_pool, music_genre_id)
+            .await?
+            .ok_or_else(|| anyhow!("音楽検索の進捗が見つかりません"))?;
+
+        Ok(music_search_progress)
+    }
+
+    // 位置を戻して完了状態を解除する。総数は次の検索で更新されるまで残す
+    pub async fn reset(db_pool: &MySqlPool, music_genre_id: u32, position: u32) -> Result<Self> {
+        sqlx::query(
             r#"
-                SELECT
-                  id, music_genre_id, position, created_at, updated_at
-                FROM
-                  music_search_progresses
-                WHERE
-                  id = ?
+                INSERT INTO
+                  music_search_progresses (music_genre_id, position)
+                VALUES
+                  (?, ?)
+                ON DUPLICATE KEY UPDATE
+                  position = ?,
+                  completed_at = NULL
             "#,
-            id
         )
-        .fetch_one(db_pool)
+        .bind(music_genre_id)
+        .bind(position)
+        .bind(position)
+        .execute(db_pool)
         .await?;
 
+        let music_search_progress = Self::find_by_music_genre_id(db_pool, music_genre_id)
+            .await?
+            .ok_or_else(|| anyhow!("音楽検索の進捗が見つかりません"))?;
+
         Ok(music_search_progress)
     }
 }
@@ -0,0 +1,99 @@
+pub mod memory;
+pub mod mysql;
+pub mod sqlite;
+
+use crate::model::{
+    excluded_artist::{self, ExcludedArtist},
+    music_genre::{self, MusicGenre},
+    music_search_progress::{self, MusicSearchProgress},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{collections::HashSet, sync::Arc};
+
+#[async_trait]
+pub trait ExcludedArtistRepository: Send + Sync {
+    // 取得・件数には除外期限が切れたものを含まない
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<ExcludedArtist>>;
+    async fn insert(&self, input: &excluded_artist::InsertInput) -> Result<ExcludedArtist>;
+    async fn bulk_insert(&self, inputs: &[excluded_artist::InsertInput]) -> Result<u64>;
+    async fn find_all(&self, input: &excluded_artist::ListInput) -> Result<Vec<ExcludedArtist>>;
+    async fn count(&self) -> Result<i64>;
+    async fn search_by_name(&self, name: &str, limit: u32) -> Result<Vec<ExcludedArtist>>;
+    async fn delete_by_ids(&self, ids: &[String]) -> Result<u64>;
+
+    // 渡されたIDのうち除外されていないもの (除外期限が切れたものを含む) を元の順序で返す
+    async fn filter_not_excluded(&self, ids: &[String]) -> Result<Vec<String>> {
+        let excluded_ids = self
+            .find_by_ids(ids)
+            .await?
+            .into_iter()
+            .map(|excluded_artist| excluded_artist.id)
+            .collect::<HashSet<_>>();
+
+        Ok(ids
+            .iter()
+            .filter(|id| !excluded_ids.contains(*id))
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+pub trait MusicGenreRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<MusicGenre>>;
+    async fn find_by_id(&self, id: u32) -> Result<Option<MusicGenre>>;
+    async fn insert(&self, input: &music_genre::InsertInput) -> Result<MusicGenre>;
+    async fn bulk_insert(&self, inputs: &[music_genre::InsertInput]) -> Result<u64>;
+    async fn update_name(&self, id: u32, name: &str) -> Result<MusicGenre>;
+    async fn update_search_key(&self, id: u32, search_key: &str) -> Result<MusicGenre>;
+    async fn delete(&self, id: u32, cascade: bool) -> Result<()>;
+}
+
+#[async_trait]
+pub trait MusicSearchProgressRepository: Send + Sync {
+    async fn find_by_music_genre_id(
+        &self,
+        music_genre_id: u32,
+    ) -> Result<Option<MusicSearchProgress>>;
+    async fn find_or_create(&self, music_genre_id: u32) -> Result<MusicSearchProgress>;
+    async fn advance(
+        &self,
+        music_genre_id: u32,
+        expected_position: Option<u32>,
+        consumed: u32,
+    ) -> Result<Option<MusicSearchProgress>>;
+    async fn record_total(&self, music_genre_id: u32, total: u32) -> Result<()>;
+    async fn complete(&self, music_genre_id: u32) -> Result<()>;
+    async fn upsert(
+        &self,
+        music_genre_id: u32,
+        input: &music_search_progress::UpsertInput,
+    ) -> Result<MusicSearchProgress>;
+    async fn reset(&self, music_genre_id: u32, position: u32) -> Result<MusicSearchProgress>;
+}
+
+#[derive(Clone)]
+pub struct Repositories {
+    pub excluded_artists: Arc<dyn ExcludedArtistRepository>,
+    pub music_genres: Arc<dyn MusicGenreRepository>,
+    pub music_search_progresses: Arc<dyn MusicSearchProgressRepository>,
+}
+
+impl Repositories {
+    pub fn new<R>(repository: R) -> Self
+    where
+        R: ExcludedArtistRepository
+            + MusicGenreRepository
+            + MusicSearchProgressRepository
+            + 'static,
+    {
+        let repository = Arc::new(repository);
+
+        Self {
+            excluded_artists: repository.clone(),
+            music_genres: repository.clone(),
+            music_search_progresses: repository,
+        }
+    }
+}
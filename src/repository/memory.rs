@@ -0,0 +1,41 @@
+mod excluded_artist;
+mod music_genre;
+mod music_search_progress;
+
+use crate::model::{
+    excluded_artist::ExcludedArtist, music_genre::MusicGenre,
+    music_search_progress::MusicSearchProgress,
+};
+use chrono::{NaiveDateTime, Utc};
+use std::sync::{Mutex, MutexGuard};
+
+// テスト向けのデータベースを使わない実装。制約や更新のルールは MySQL 版と同じ結果になるように書く
+#[derive(Default)]
+pub struct MemoryRepository {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    excluded_artists: Vec<ExcludedArtist>,
+    music_genres: Vec<MusicGenre>,
+    music_search_progresses: Vec<MusicSearchProgress>,
+    last_music_genre_id: u32,
+    last_music_search_progress_id: u32,
+}
+
+impl MemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("ロックを保持したままパニックしない")
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
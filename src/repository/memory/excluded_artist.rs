@@ -0,0 +1,169 @@
+use crate::{
+    model::{
+        excluded_artist::{ExcludedArtist, InsertInput, ListInput},
+        sort_order::SortOrder,
+    },
+    repository::{
+        ExcludedArtistRepository,
+        memory::{MemoryRepository, State, now},
+    },
+};
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+impl ExcludedArtist {
+    fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+impl State {
+    fn check_source_music_genre(&self, input: &InsertInput) -> Result<()> {
+        if let Some(music_genre_id) = input.source_music_genre_id
+            && !self
+                .music_genres
+                .iter()
+                .any(|genre| genre.id == music_genre_id)
+        {
+            bail!("音楽ジャンルが存在しません (ID: {})", music_genre_id);
+        }
+
+        Ok(())
+    }
+
+    // 登録済みのIDは呼び出し元で無視できるように None を返す。除外期限が切れた行は登録し直す
+    fn insert_excluded_artist(&mut self, input: &InsertInput) -> Option<ExcludedArtist> {
+        let now = now();
+        self.excluded_artists.retain(|excluded_artist| {
+            excluded_artist.id != input.id || excluded_artist.is_active(now)
+        });
+        if self
+            .excluded_artists
+            .iter()
+            .any(|excluded_artist| excluded_artist.id == input.id)
+        {
+            return None;
+        }
+        let excluded_artist = ExcludedArtist {
+            id: input.id.clone(),
+            name: input.name.clone(),
+            reason: input.reason.clone(),
+            category: input.category,
+            expires_at: input.expires_at,
+            source_music_genre_id: input.source_music_genre_id,
+            created_at: now,
+        };
+        self.excluded_artists.push(excluded_artist.clone());
+
+        Some(excluded_artist)
+    }
+}
+
+#[async_trait]
+impl ExcludedArtistRepository for MemoryRepository {
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<ExcludedArtist>> {
+        let now = now();
+        let mut excluded_artists = self
+            .state()
+            .excluded_artists
+            .iter()
+            .filter(|excluded_artist| excluded_artist.is_active(now))
+            .filter(|excluded_artist| ids.contains(&excluded_artist.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        excluded_artists.sort_by(|a, b| b.id.cmp(&a.id));
+
+        Ok(excluded_artists)
+    }
+
+    async fn insert(&self, input: &InsertInput) -> Result<ExcludedArtist> {
+        let mut state = self.state();
+        state.check_source_music_genre(input)?;
+        match state.insert_excluded_artist(input) {
+            Some(excluded_artist) => Ok(excluded_artist),
+            None => bail!("アーティストは登録済みです: {}", input.id),
+        }
+    }
+
+    // データベースではトランザクション内で登録するため、1件でも登録できなければ何も登録しない
+    async fn bulk_insert(&self, inputs: &[InsertInput]) -> Result<u64> {
+        let mut state = self.state();
+        for input in inputs {
+            state.check_source_music_genre(input)?;
+        }
+        let inserted = inputs
+            .iter()
+            .filter_map(|input| state.insert_excluded_artist(input))
+            .count();
+
+        Ok(inserted as u64)
+    }
+
+    async fn find_all(&self, input: &ListInput) -> Result<Vec<ExcludedArtist>> {
+        let now = now();
+        let mut excluded_artists = self
+            .state()
+            .excluded_artists
+            .iter()
+            .filter(|excluded_artist| excluded_artist.is_active(now))
+            .cloned()
+            .collect::<Vec<_>>();
+        excluded_artists.sort_by(|a, b| {
+            let ordering = a
+                .created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.cmp(&b.id));
+            match input.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+
+        Ok(excluded_artists
+            .into_iter()
+            .skip(input.offset as usize)
+            .take(input.limit as usize)
+            .collect())
+    }
+
+    async fn count(&self) -> Result<i64> {
+        let now = now();
+        let count = self
+            .state()
+            .excluded_artists
+            .iter()
+            .filter(|excluded_artist| excluded_artist.is_active(now))
+            .count();
+
+        Ok(count as i64)
+    }
+
+    async fn search_by_name(&self, name: &str, limit: u32) -> Result<Vec<ExcludedArtist>> {
+        // データベースの照合順序に合わせて大文字と小文字を区別しない
+        let now = now();
+        let name = name.to_lowercase();
+        let mut excluded_artists = self
+            .state()
+            .excluded_artists
+            .iter()
+            .filter(|excluded_artist| excluded_artist.is_active(now))
+            .filter(|excluded_artist| excluded_artist.name.to_lowercase().contains(&name))
+            .cloned()
+            .collect::<Vec<_>>();
+        excluded_artists.sort_by(|a, b| a.name.cmp(&b.name));
+        excluded_artists.truncate(limit as usize);
+
+        Ok(excluded_artists)
+    }
+
+    async fn delete_by_ids(&self, ids: &[String]) -> Result<u64> {
+        let mut state = self.state();
+        let before = state.excluded_artists.len();
+        state
+            .excluded_artists
+            .retain(|excluded_artist| !ids.contains(&excluded_artist.id));
+
+        Ok((before - state.excluded_artists.len()) as u64)
+    }
+}
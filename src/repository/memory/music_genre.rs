@@ -0,0 +1,124 @@
+use crate::{
+    model::music_genre::{InsertInput, MusicGenre, error::MusicGenreError, validate_search_key},
+    repository::{
+        MusicGenreRepository,
+        memory::{MemoryRepository, State, now},
+    },
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+impl State {
+    fn has_search_key(&self, search_key: &str, except_id: Option<u32>) -> bool {
+        self.music_genres
+            .iter()
+            .any(|genre| genre.search_key == search_key && Some(genre.id) != except_id)
+    }
+
+    fn insert_music_genre(&mut self, input: &InsertInput) -> Option<MusicGenre> {
+        if self.has_search_key(&input.search_key, None) {
+            return None;
+        }
+        self.last_music_genre_id += 1;
+        let now = now();
+        let genre = MusicGenre {
+            id: self.last_music_genre_id,
+            name: input.name.clone(),
+            search_key: input.search_key.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.music_genres.push(genre.clone());
+
+        Some(genre)
+    }
+
+    fn music_genre_mut(&mut self, id: u32) -> Result<&mut MusicGenre> {
+        self.music_genres
+            .iter_mut()
+            .find(|genre| genre.id == id)
+            .ok_or_else(|| MusicGenreError::NotFound(id).into())
+    }
+}
+
+#[async_trait]
+impl MusicGenreRepository for MemoryRepository {
+    async fn find_all(&self) -> Result<Vec<MusicGenre>> {
+        Ok(self.state().music_genres.clone())
+    }
+
+    async fn find_by_id(&self, id: u32) -> Result<Option<MusicGenre>> {
+        Ok(self
+            .state()
+            .music_genres
+            .iter()
+            .find(|genre| genre.id == id)
+            .cloned())
+    }
+
+    async fn insert(&self, input: &InsertInput) -> Result<MusicGenre> {
+        validate_search_key(&input.search_key)?;
+        self.state()
+            .insert_music_genre(input)
+            .ok_or_else(|| MusicGenreError::DuplicateSearchKey(input.search_key.clone()).into())
+    }
+
+    async fn bulk_insert(&self, inputs: &[InsertInput]) -> Result<u64> {
+        for input in inputs {
+            validate_search_key(&input.search_key)?;
+        }
+        let mut state = self.state();
+        let inserted = inputs
+            .iter()
+            .filter_map(|input| state.insert_music_genre(input))
+            .count();
+
+        Ok(inserted as u64)
+    }
+
+    async fn update_name(&self, id: u32, name: &str) -> Result<MusicGenre> {
+        let mut state = self.state();
+        let genre = state.music_genre_mut(id)?;
+        genre.name = name.to_string();
+        genre.updated_at = now();
+
+        Ok(genre.clone())
+    }
+
+    async fn update_search_key(&self, id: u32, search_key: &str) -> Result<MusicGenre> {
+        validate_search_key(search_key)?;
+        let mut state = self.state();
+        if state.has_search_key(search_key, Some(id)) {
+            return Err(MusicGenreError::DuplicateSearchKey(search_key.to_string()).into());
+        }
+        let genre = state.music_genre_mut(id)?;
+        genre.search_key = search_key.to_string();
+        genre.updated_at = now();
+
+        Ok(genre.clone())
+    }
+
+    async fn delete(&self, id: u32, cascade: bool) -> Result<()> {
+        let mut state = self.state();
+        state.music_genre_mut(id)?;
+        let referenced = state
+            .music_search_progresses
+            .iter()
+            .any(|progress| progress.music_genre_id == id);
+        if referenced && !cascade {
+            return Err(MusicGenreError::Referenced(id).into());
+        }
+        state
+            .music_search_progresses
+            .retain(|progress| progress.music_genre_id != id);
+        state.music_genres.retain(|genre| genre.id != id);
+        // 外部キーの ON DELETE SET NULL に合わせる
+        for excluded_artist in &mut state.excluded_artists {
+            if excluded_artist.source_music_genre_id == Some(id) {
+                excluded_artist.source_music_genre_id = None;
+            }
+        }
+
+        Ok(())
+    }
+}
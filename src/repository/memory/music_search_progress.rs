@@ -0,0 +1,157 @@
+use crate::{
+    constant::{music_search::INITIAL_POSITION, spotify::SEARCH_OFFSET_LIMIT},
+    model::music_search_progress::{MusicSearchProgress, UpsertInput},
+    repository::{
+        MusicSearchProgressRepository,
+        memory::{MemoryRepository, State, now},
+    },
+};
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+
+impl MusicSearchProgress {
+    // MySQL 版の UPDATE と同じく、到達可能な件数まで進んだら完了にし、戻ったら未完了に戻す
+    fn set_position(&mut self, position: u32) {
+        let now = now();
+        self.position = position;
+        self.completed_at = match self.position >= reachable_limit(self.last_total) {
+            true => self.completed_at.or(Some(now)),
+            false => None,
+        };
+        self.updated_at = now;
+    }
+}
+
+impl State {
+    fn music_search_progress_mut(
+        &mut self,
+        music_genre_id: u32,
+    ) -> Option<&mut MusicSearchProgress> {
+        self.music_search_progresses
+            .iter_mut()
+            .find(|progress| progress.music_genre_id == music_genre_id)
+    }
+
+    fn insert_music_search_progress(
+        &mut self,
+        music_genre_id: u32,
+        position: u32,
+    ) -> Result<MusicSearchProgress> {
+        if !self
+            .music_genres
+            .iter()
+            .any(|genre| genre.id == music_genre_id)
+        {
+            bail!("音楽ジャンルが存在しません: {}", music_genre_id);
+        }
+        self.last_music_search_progress_id += 1;
+        let now = now();
+        let progress = MusicSearchProgress {
+            id: self.last_music_search_progress_id,
+            music_genre_id,
+            position,
+            last_total: None,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.music_search_progresses.push(progress.clone());
+
+        Ok(progress)
+    }
+}
+
+fn reachable_limit(last_total: Option<u32>) -> u32 {
+    last_total
+        .unwrap_or(SEARCH_OFFSET_LIMIT)
+        .min(SEARCH_OFFSET_LIMIT)
+}
+
+#[async_trait]
+impl MusicSearchProgressRepository for MemoryRepository {
+    async fn find_by_music_genre_id(
+        &self,
+        music_genre_id: u32,
+    ) -> Result<Option<MusicSearchProgress>> {
+        Ok(self
+            .state()
+            .music_search_progress_mut(music_genre_id)
+            .map(|progress| progress.clone()))
+    }
+
+    async fn find_or_create(&self, music_genre_id: u32) -> Result<MusicSearchProgress> {
+        let mut state = self.state();
+        if let Some(progress) = state.music_search_progress_mut(music_genre_id) {
+            return Ok(progress.clone());
+        }
+
+        state.insert_music_search_progress(music_genre_id, INITIAL_POSITION)
+    }
+
+    async fn advance(
+        &self,
+        music_genre_id: u32,
+        expected_position: Option<u32>,
+        consumed: u32,
+    ) -> Result<Option<MusicSearchProgress>> {
+        let mut state = self.state();
+        let Some(progress) = state
+            .music_search_progress_mut(music_genre_id)
+            .filter(|progress| {
+                expected_position.is_none_or(|position| progress.position == position)
+            })
+        else {
+            return Ok(None);
+        };
+        let limit = reachable_limit(progress.last_total).max(progress.position);
+        progress.set_position(progress.position.saturating_add(consumed).min(limit));
+
+        Ok(Some(progress.clone()))
+    }
+
+    async fn record_total(&self, music_genre_id: u32, total: u32) -> Result<()> {
+        if let Some(progress) = self.state().music_search_progress_mut(music_genre_id) {
+            progress.last_total = Some(total);
+            progress.set_position(progress.position);
+        }
+
+        Ok(())
+    }
+
+    async fn complete(&self, music_genre_id: u32) -> Result<()> {
+        if let Some(progress) = self.state().music_search_progress_mut(music_genre_id) {
+            progress.completed_at = progress.completed_at.or(Some(now()));
+            progress.updated_at = now();
+        }
+
+        Ok(())
+    }
+
+    async fn upsert(
+        &self,
+        music_genre_id: u32,
+        input: &UpsertInput,
+    ) -> Result<MusicSearchProgress> {
+        let mut state = self.state();
+        match state.music_search_progress_mut(music_genre_id) {
+            Some(progress) => {
+                progress.set_position(input.position);
+                Ok(progress.clone())
+            }
+            None => state.insert_music_search_progress(music_genre_id, input.position),
+        }
+    }
+
+    async fn reset(&self, music_genre_id: u32, position: u32) -> Result<MusicSearchProgress> {
+        let mut state = self.state();
+        match state.music_search_progress_mut(music_genre_id) {
+            Some(progress) => {
+                progress.position = position;
+                progress.completed_at = None;
+                progress.updated_at = now();
+                Ok(progress.clone())
+            }
+            None => state.insert_music_search_progress(music_genre_id, position),
+        }
+    }
+}
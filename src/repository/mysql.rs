@@ -0,0 +1,128 @@
+use crate::{
+    model::{
+        excluded_artist::{self, ExcludedArtist},
+        music_genre::{self, MusicGenre},
+        music_search_progress::{self, MusicSearchProgress},
+    },
+    repository::{ExcludedArtistRepository, MusicGenreRepository, MusicSearchProgressRepository},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+// SQL はモデルに定義しているため、ここではプールを渡して呼び出すだけにする
+pub struct MySqlRepository {
+    db_pool: MySqlPool,
+}
+
+impl MySqlRepository {
+    pub fn new(db_pool: MySqlPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl ExcludedArtistRepository for MySqlRepository {
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<ExcludedArtist>> {
+        ExcludedArtist::find_by_ids(&self.db_pool, ids).await
+    }
+
+    async fn insert(&self, input: &excluded_artist::InsertInput) -> Result<ExcludedArtist> {
+        ExcludedArtist::insert(&self.db_pool, input).await
+    }
+
+    async fn bulk_insert(&self, inputs: &[excluded_artist::InsertInput]) -> Result<u64> {
+        ExcludedArtist::bulk_insert(&self.db_pool, inputs).await
+    }
+
+    async fn find_all(&self, input: &excluded_artist::ListInput) -> Result<Vec<ExcludedArtist>> {
+        ExcludedArtist::find_all(&self.db_pool, input).await
+    }
+
+    async fn count(&self) -> Result<i64> {
+        ExcludedArtist::count(&self.db_pool).await
+    }
+
+    async fn search_by_name(&self, name: &str, limit: u32) -> Result<Vec<ExcludedArtist>> {
+        ExcludedArtist::search_by_name(&self.db_pool, name, limit).await
+    }
+
+    async fn delete_by_ids(&self, ids: &[String]) -> Result<u64> {
+        ExcludedArtist::delete_by_ids(&self.db_pool, ids).await
+    }
+}
+
+#[async_trait]
+impl MusicGenreRepository for MySqlRepository {
+    async fn find_all(&self) -> Result<Vec<MusicGenre>> {
+        MusicGenre::find_all(&self.db_pool).await
+    }
+
+    async fn find_by_id(&self, id: u32) -> Result<Option<MusicGenre>> {
+        MusicGenre::find_by_id(&self.db_pool, id).await
+    }
+
+    async fn insert(&self, input: &music_genre::InsertInput) -> Result<MusicGenre> {
+        MusicGenre::insert(&self.db_pool, input).await
+    }
+
+    async fn bulk_insert(&self, inputs: &[music_genre::InsertInput]) -> Result<u64> {
+        MusicGenre::bulk_insert(&self.db_pool, inputs).await
+    }
+
+    async fn update_name(&self, id: u32, name: &str) -> Result<MusicGenre> {
+        MusicGenre::update_name(&self.db_pool, id, name).await
+    }
+
+    async fn update_search_key(&self, id: u32, search_key: &str) -> Result<MusicGenre> {
+        MusicGenre::update_search_key(&self.db_pool, id, search_key).await
+    }
+
+    async fn delete(&self, id: u32, cascade: bool) -> Result<()> {
+        MusicGenre::delete(&self.db_pool, id, cascade).await
+    }
+}
+
+#[async_trait]
+impl MusicSearchProgressRepository for MySqlRepository {
+    async fn find_by_music_genre_id(
+        &self,
+        music_genre_id: u32,
+    ) -> Result<Option<MusicSearchProgress>> {
+        MusicSearchProgress::find_by_music_genre_id(&self.db_pool, music_genre_id).await
+    }
+
+    async fn find_or_create(&self, music_genre_id: u32) -> Result<MusicSearchProgress> {
+        MusicSearchProgress::find_or_create(&self.db_pool, music_genre_id).await
+    }
+
+    async fn advance(
+        &self,
+        music_genre_id: u32,
+        expected_position: Option<u32>,
+        consumed: u32,
+    ) -> Result<Option<MusicSearchProgress>> {
+        MusicSearchProgress::advance(&self.db_pool, music_genre_id, expected_position, consumed)
+            .await
+    }
+
+    async fn record_total(&self, music_genre_id: u32, total: u32) -> Result<()> {
+        MusicSearchProgress::record_total(&self.db_pool, music_genre_id, total).await
+    }
+
+    async fn complete(&self, music_genre_id: u32) -> Result<()> {
+        MusicSearchProgress::complete(&self.db_pool, music_genre_id).await
+    }
+
+    async fn upsert(
+        &self,
+        music_genre_id: u32,
+        input: &music_search_progress::UpsertInput,
+    ) -> Result<MusicSearchProgress> {
+        MusicSearchProgress::upsert(&self.db_pool, music_genre_id, input).await
+    }
+
+    async fn reset(&self, music_genre_id: u32, position: u32) -> Result<MusicSearchProgress> {
+        MusicSearchProgress::reset(&self.db_pool, music_genre_id, position).await
+    }
+}
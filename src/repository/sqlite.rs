@@ -0,0 +1,16 @@
+mod excluded_artist;
+mod music_genre;
+mod music_search_progress;
+
+use sqlx::SqlitePool;
+
+// 個人利用やテスト向けの組み込みデータベース。SQL は MySQL 版と同じ結果になるように書く
+pub struct SqliteRepository {
+    db_pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    pub fn new(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    constant::database::IN_QUERY_CHUNK_SIZE,
+    model::excluded_artist::{ExcludedArtist, InsertInput, ListInput, like_pattern},
+    repository::{ExcludedArtistRepository, sqlite::SqliteRepository},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
+
+#[async_trait]
+impl ExcludedArtistRepository for SqliteRepository {
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<ExcludedArtist>> {
+        let mut excluded_artists = Vec::new();
+        for chunk in ids.chunks(IN_QUERY_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<Sqlite>::new(
+                r#"
+                  SELECT
+                    id,
+                    name,
+                    reason,
+                    category,
+                    expires_at,
+                    source_music_genre_id,
+                    created_at
+                  FROM
+                    excluded_artists
+                  WHERE
+                    (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+                    AND id IN (
+                "#,
+            );
+            let mut separated = query.separated(", ");
+            for id in chunk {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+            excluded_artists.extend(
+                query
+                    .build_query_as::<ExcludedArtist>()
+                    .fetch_all(&self.db_pool)
+                    .await?,
+            );
+        }
+        excluded_artists.sort_by(|a, b| b.id.cmp(&a.id));
+
+        Ok(excluded_artists)
+    }
+
+    async fn insert(&self, input: &InsertInput) -> Result<ExcludedArtist> {
+        let mut tx = self.db_pool.begin().await?;
+        delete_expired(&mut tx, &[input.id.as_str()]).await?;
+        let excluded_artist = sqlx::query_as::<_, ExcludedArtist>(
+            r#"
+              INSERT INTO
+                excluded_artists (
+                  id,
+                  name,
+                  reason,
+                  category,
+                  expires_at,
+                  source_music_genre_id
+                )
+              VALUES
+                (?, ?, ?, ?, ?, ?)
+              RETURNING
+                id,
+                name,
+                reason,
+                category,
+                expires_at,
+                source_music_genre_id,
+                created_at
+            "#,
+        )
+        .bind(&input.id)
+        .bind(&input.name)
+        .bind(&input.reason)
+        .bind(input.category)
+        .bind(input.expires_at)
+        .bind(input.source_music_genre_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(excluded_artist)
+    }
+
+    // INSERT OR IGNORE は CHECK 制約の違反なども無視するため、ID の重複だけを無視する
+    async fn bulk_insert(&self, inputs: &[InsertInput]) -> Result<u64> {
+        let mut tx = self.db_pool.begin().await?;
+        let mut inserted = 0;
+        for chunk in inputs.chunks(IN_QUERY_CHUNK_SIZE) {
+            let ids = chunk
+                .iter()
+                .map(|input| input.id.as_str())
+                .collect::<Vec<_>>();
+            delete_expired(&mut tx, &ids).await?;
+            let mut query = QueryBuilder::<Sqlite>::new(
+                r#"
+                  INSERT INTO
+                    excluded_artists (
+                      id,
+                      name,
+                      reason,
+                      category,
+                      expires_at,
+                      source_music_genre_id
+                    )
+                "#,
+            );
+            query.push_values(chunk, |mut row, input| {
+                row.push_bind(&input.id)
+                    .push_bind(&input.name)
+                    .push_bind(&input.reason)
+                    .push_bind(input.category)
+                    .push_bind(input.expires_at)
+                    .push_bind(input.source_music_genre_id);
+            });
+            query.push(
+                r#"
+                  ON CONFLICT (id) DO NOTHING
+                "#,
+            );
+            inserted += query.build().execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+
+        Ok(inserted)
+    }
+
+    async fn find_all(&self, input: &ListInput) -> Result<Vec<ExcludedArtist>> {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"
+              SELECT
+                id,
+                name,
+                reason,
+                category,
+                expires_at,
+                source_music_genre_id,
+                created_at
+              FROM
+                excluded_artists
+              WHERE
+                expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP
+              ORDER BY
+                created_at
+            "#,
+        );
+        query
+            .push(input.order.as_sql())
+            .push(", id ")
+            .push(input.order.as_sql())
+            .push(" LIMIT ")
+            .push_bind(input.limit)
+            .push(" OFFSET ")
+            .push_bind(input.offset);
+        let excluded_artists = query
+            .build_query_as::<ExcludedArtist>()
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(excluded_artists)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            r#"
+              SELECT
+                COUNT(*)
+              FROM
+                excluded_artists
+              WHERE
+                expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP
+            "#,
+        )
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn search_by_name(&self, name: &str, limit: u32) -> Result<Vec<ExcludedArtist>> {
+        // SQLite の LIKE はエスケープ文字を明示する必要がある
+        let excluded_artists = sqlx::query_as::<_, ExcludedArtist>(
+            r#"
+              SELECT
+                id,
+                name,
+                reason,
+                category,
+                expires_at,
+                source_music_genre_id,
+                created_at
+              FROM
+                excluded_artists
+              WHERE
+                (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+                AND name LIKE ? ESCAPE '\'
+              ORDER BY
+                name ASC
+              LIMIT ?
+            "#,
+        )
+        .bind(like_pattern(name))
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(excluded_artists)
+    }
+
+    async fn delete_by_ids(&self, ids: &[String]) -> Result<u64> {
+        let mut deleted = 0;
+        for chunk in ids.chunks(IN_QUERY_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<Sqlite>::new(
+                r#"
+                  DELETE FROM
+                    excluded_artists
+                  WHERE
+                    id IN (
+                "#,
+            );
+            let mut separated = query.separated(", ");
+            for id in chunk {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+            deleted += query.build().execute(&self.db_pool).await?.rows_affected();
+        }
+
+        Ok(deleted)
+    }
+}
+
+// 除外期限が切れた行は登録し直せるように削除する
+async fn delete_expired(tx: &mut SqliteConnection, ids: &[&str]) -> Result<()> {
+    let mut query = QueryBuilder::<Sqlite>::new(
+        r#"
+          DELETE FROM
+            excluded_artists
+          WHERE
+            expires_at <= CURRENT_TIMESTAMP
+            AND id IN (
+        "#,
+    );
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(*id);
+    }
+    separated.push_unseparated(")");
+    query.build().execute(tx).await?;
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use crate::{
+    model::music_genre::{
+        InsertInput, MusicGenre, error::MusicGenreError, unique_violation, validate_search_key,
+    },
+    repository::{MusicGenreRepository, sqlite::SqliteRepository},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::{QueryBuilder, Sqlite};
+
+#[async_trait]
+impl MusicGenreRepository for SqliteRepository {
+    async fn find_all(&self) -> Result<Vec<MusicGenre>> {
+        let genres = sqlx::query_as::<_, MusicGenre>(
+            r#"
+                SELECT
+                  id, name, search_key, created_at, updated_at
+                FROM
+                  music_genres
+                ORDER BY
+                  id ASC
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(genres)
+    }
+
+    async fn find_by_id(&self, id: u32) -> Result<Option<MusicGenre>> {
+        let genre = sqlx::query_as::<_, MusicGenre>(
+            r#"
+                SELECT
+                  id, name, search_key, created_at, updated_at
+                FROM
+                  music_genres
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(genre)
+    }
+
+    async fn insert(&self, input: &InsertInput) -> Result<MusicGenre> {
+        validate_search_key(&input.search_key)?;
+        let genre = sqlx::query_as::<_, MusicGenre>(
+            r#"
+                INSERT INTO
+                  music_genres (name, search_key)
+                VALUES
+                  (?, ?)
+                RETURNING
+                  id, name, search_key, created_at, updated_at
+            "#,
+        )
+        .bind(&input.name)
+        .bind(&input.search_key)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| unique_violation(e, &input.search_key))?;
+
+        Ok(genre)
+    }
+
+    async fn bulk_insert(&self, inputs: &[InsertInput]) -> Result<u64> {
+        if inputs.is_empty() {
+            return Ok(0);
+        }
+        for input in inputs {
+            validate_search_key(&input.search_key)?;
+        }
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"
+                INSERT INTO
+                  music_genres (name, search_key)
+            "#,
+        );
+        query.push_values(inputs, |mut row, input| {
+            row.push_bind(&input.name).push_bind(&input.search_key);
+        });
+        query.push(
+            r#"
+                ON CONFLICT (search_key) DO NOTHING
+            "#,
+        );
+        let inserted = query.build().execute(&self.db_pool).await?.rows_affected();
+
+        Ok(inserted)
+    }
+
+    async fn update_name(&self, id: u32, name: &str) -> Result<MusicGenre> {
+        let genre = sqlx::query_as::<_, MusicGenre>(
+            r#"
+                UPDATE
+                  music_genres
+                SET
+                  name = ?
+                WHERE
+                  id = ?
+                RETURNING
+                  id, name, search_key, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        genre.ok_or_else(|| MusicGenreError::NotFound(id).into())
+    }
+
+    async fn update_search_key(&self, id: u32, search_key: &str) -> Result<MusicGenre> {
+        validate_search_key(search_key)?;
+        let genre = sqlx::query_as::<_, MusicGenre>(
+            r#"
+                UPDATE
+                  music_genres
+                SET
+                  search_key = ?
+                WHERE
+                  id = ?
+                RETURNING
+                  id, name, search_key, created_at, updated_at
+            "#,
+        )
+        .bind(search_key)
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| unique_violation(e, search_key))?;
+
+        genre.ok_or_else(|| MusicGenreError::NotFound(id).into())
+    }
+
+    async fn delete(&self, id: u32, cascade: bool) -> Result<()> {
+        let mut tx = self.db_pool.begin().await?;
+        let genre: Option<u32> = sqlx::query_scalar(
+            r#"
+                SELECT
+                  id
+                FROM
+                  music_genres
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if genre.is_none() {
+            return Err(MusicGenreError::NotFound(id).into());
+        }
+
+        if cascade {
+            sqlx::query(
+                r#"
+                    DELETE FROM
+                      music_search_progresses
+                    WHERE
+                      music_genre_id = ?
+                "#,
+            )
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            let referenced: i64 = sqlx::query_scalar(
+                r#"
+                    SELECT
+                      COUNT(*)
+                    FROM
+                      music_search_progresses
+                    WHERE
+                      music_genre_id = ?
+                "#,
+            )
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if referenced > 0 {
+                return Err(MusicGenreError::Referenced(id).into());
+            }
+        }
+
+        sqlx::query(
+            r#"
+                DELETE FROM
+                  music_genres
+                WHERE
+                  id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
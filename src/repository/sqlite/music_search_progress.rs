@@ -0,0 +1,181 @@
+use crate::{
+    constant::{music_search::INITIAL_POSITION, spotify::SEARCH_OFFSET_LIMIT},
+    model::music_search_progress::{MusicSearchProgress, UpsertInput},
+    repository::{MusicSearchProgressRepository, sqlite::SqliteRepository},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+// SQLite の UPDATE は SET 内で更新前の値を参照するため、MySQL 版と違い更新後の position を式で書き直す
+#[async_trait]
+impl MusicSearchProgressRepository for SqliteRepository {
+    async fn find_by_music_genre_id(
+        &self,
+        music_genre_id: u32,
+    ) -> Result<Option<MusicSearchProgress>> {
+        let result = sqlx::query_as::<_, MusicSearchProgress>(
+            r#"
+                SELECT
+                  id, music_genre_id, position, last_total, completed_at, created_at, updated_at
+                FROM
+                  music_search_progresses
+                WHERE
+                  music_genre_id = ?
+            "#,
+        )
+        .bind(music_genre_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn find_or_create(&self, music_genre_id: u32) -> Result<MusicSearchProgress> {
+        sqlx::query(
+            r#"
+                INSERT OR IGNORE INTO
+                  music_search_progresses (music_genre_id, position)
+                VALUES
+                  (?, ?)
+            "#,
+        )
+        .bind(music_genre_id)
+        .bind(INITIAL_POSITION)
+        .execute(&self.db_pool)
+        .await?;
+
+        self.find_by_music_genre_id(music_genre_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("音楽検索の進捗が見つかりません"))
+    }
+
+    async fn advance(
+        &self,
+        music_genre_id: u32,
+        expected_position: Option<u32>,
+        consumed: u32,
+    ) -> Result<Option<MusicSearchProgress>> {
+        let progress = sqlx::query_as::<_, MusicSearchProgress>(
+            r#"
+                UPDATE
+                  music_search_progresses
+                SET
+                  position = MIN(position + ?1, MAX(position, MIN(COALESCE(last_total, ?2), ?2))),
+                  completed_at = CASE
+                    WHEN MIN(position + ?1, MAX(position, MIN(COALESCE(last_total, ?2), ?2)))
+                      >= MIN(COALESCE(last_total, ?2), ?2) THEN COALESCE(completed_at, CURRENT_TIMESTAMP)
+                    ELSE NULL
+                  END
+                WHERE
+                  music_genre_id = ?3
+                  AND (?4 IS NULL OR position = ?4)
+                RETURNING
+                  id, music_genre_id, position, last_total, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(consumed)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(music_genre_id)
+        .bind(expected_position)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    async fn record_total(&self, music_genre_id: u32, total: u32) -> Result<()> {
+        sqlx::query(
+            r#"
+                UPDATE
+                  music_search_progresses
+                SET
+                  last_total = ?1,
+                  completed_at = CASE
+                    WHEN position >= MIN(?1, ?2) THEN COALESCE(completed_at, CURRENT_TIMESTAMP)
+                    ELSE NULL
+                  END
+                WHERE
+                  music_genre_id = ?3
+            "#,
+        )
+        .bind(total)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .bind(music_genre_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn complete(&self, music_genre_id: u32) -> Result<()> {
+        sqlx::query(
+            r#"
+                UPDATE
+                  music_search_progresses
+                SET
+                  completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP)
+                WHERE
+                  music_genre_id = ?
+            "#,
+        )
+        .bind(music_genre_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert(
+        &self,
+        music_genre_id: u32,
+        input: &UpsertInput,
+    ) -> Result<MusicSearchProgress> {
+        let progress = sqlx::query_as::<_, MusicSearchProgress>(
+            r#"
+                INSERT INTO
+                  music_search_progresses (music_genre_id, position)
+                VALUES
+                  (?1, ?2)
+                ON CONFLICT (music_genre_id) DO UPDATE
+                SET
+                  position = excluded.position,
+                  completed_at = CASE
+                    WHEN excluded.position >= MIN(COALESCE(last_total, ?3), ?3) THEN COALESCE(completed_at, CURRENT_TIMESTAMP)
+                    ELSE NULL
+                  END
+                RETURNING
+                  id, music_genre_id, position, last_total, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(music_genre_id)
+        .bind(input.position)
+        .bind(SEARCH_OFFSET_LIMIT)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    async fn reset(&self, music_genre_id: u32, position: u32) -> Result<MusicSearchProgress> {
+        let progress = sqlx::query_as::<_, MusicSearchProgress>(
+            r#"
+                INSERT INTO
+                  music_search_progresses (music_genre_id, position)
+                VALUES
+                  (?, ?)
+                ON CONFLICT (music_genre_id) DO UPDATE
+                SET
+                  position = excluded.position,
+                  completed_at = NULL
+                RETURNING
+                  id, music_genre_id, position, last_total, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(music_genre_id)
+        .bind(position)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(progress)
+    }
+}
@@ -0,0 +1,1606 @@
+use crate::{
+    client::spotify::{
+        self, SpotifyClient,
+        error::SpotifyError,
+        v1::me::player::{
+            devices::Device,
+            play::{Offset, PutBody},
+            repeat::RepeatState,
+        },
+    },
+    config::Config,
+    constant::{self, excluded_artist, music_search},
+    model::{
+        excluded_artist::{ExcludedArtist, InsertInput, ListInput},
+        exclusion_category::ExclusionCategory,
+        music_genre::{self, error::MusicGenreError},
+        music_search_progress::{self, MusicSearchProgress},
+        sort_order::SortOrder,
+    },
+    repository::Repositories,
+};
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use futures::TryStreamExt;
+use rmcp::{
+    Error as McpError, ServerHandler,
+    model::{CallToolResult, Content, ErrorCode},
+    tool,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchQuery {
+    #[schemars(description = "ジャンル")]
+    pub genre: String,
+    #[schemars(description = "現在の検索位置")]
+    pub position: u32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct IsFollowingQuery {
+    #[schemars(description = "アーティストIDの配列")]
+    pub ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct FollowStatus {
+    id: String,
+    name: Option<String>,
+    following: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct PlayQuery {
+    #[schemars(description = "再生するアルバム・アーティスト・プレイリストのURI")]
+    pub context_uri: Option<String>,
+    #[schemars(description = "再生する曲のURIの配列。context_uri とは同時に指定できません")]
+    pub uris: Option<Vec<String>>,
+    #[schemars(description = "コンテキストまたは uris の中で再生を始める曲の位置 (0始まり)")]
+    pub offset_position: Option<u32>,
+    #[schemars(description = "コンテキストまたは uris の中で再生を始める曲のURI")]
+    pub offset_uri: Option<String>,
+    #[schemars(description = "曲の再生開始位置 (ミリ秒)")]
+    pub position_ms: Option<u32>,
+    #[schemars(description = "再生するデバイスID (省略時は現在アクティブなデバイス)")]
+    pub device_id: Option<String>,
+    #[schemars(
+        description = "true の場合、device_id がなくアクティブなデバイスもなければ、設定で優先するデバイスまたは操作できる最初のデバイスで再生します (デフォルト false)"
+    )]
+    pub auto_select_device: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct TransferPlaybackQuery {
+    #[schemars(description = "再生を移すデバイスID")]
+    pub device_id: String,
+    #[schemars(
+        description = "true の場合は移した後に再生を始めます。false の場合は現在の再生・一時停止の状態を引き継ぎます (デフォルト false)"
+    )]
+    pub play: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DeviceQuery {
+    #[schemars(description = "操作するデバイスID (省略時は現在アクティブなデバイス)")]
+    pub device_id: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SeekQuery {
+    #[schemars(description = "移動先の再生位置 (ミリ秒)")]
+    pub position_ms: u32,
+    #[schemars(description = "操作するデバイスID (省略時は現在アクティブなデバイス)")]
+    pub device_id: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SetVolumeQuery {
+    #[schemars(description = "音量 (0から100)")]
+    pub volume_percent: u32,
+    #[schemars(description = "操作するデバイスID (省略時は現在アクティブなデバイス)")]
+    pub device_id: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SetShuffleQuery {
+    #[schemars(description = "true でシャッフルをオン、false でオフにします")]
+    pub state: bool,
+    #[schemars(description = "操作するデバイスID (省略時は現在アクティブなデバイス)")]
+    pub device_id: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SetRepeatQuery {
+    #[schemars(
+        description = "リピートの種類 (track: 1曲、context: アルバム・プレイリスト、off: オフ)"
+    )]
+    pub state: RepeatState,
+    #[schemars(description = "操作するデバイスID (省略時は現在アクティブなデバイス)")]
+    pub device_id: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct FollowQuery {
+    #[schemars(description = "アーティストID")]
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetExcludedArtistsByIdsQuery {
+    #[schemars(description = "アーティストIDの配列")]
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct FilterNotExcludedArtistsQuery {
+    #[schemars(description = "アーティストIDの配列")]
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct InsertExcludedArtistQuery {
+    #[schemars(description = "アーティストID")]
+    pub id: String,
+    #[schemars(description = "アーティスト名")]
+    pub name: String,
+    #[schemars(description = "除外した理由")]
+    pub reason: Option<String>,
+    #[schemars(description = "除外の分類")]
+    pub category: Option<ExclusionCategory>,
+    #[schemars(
+        description = "除外の有効期限 (UTC の YYYY-MM-DD または YYYY-MM-DD HH:MM:SS)。この日時以降は除外されていないものとして扱います"
+    )]
+    pub expires_at: Option<String>,
+    #[schemars(description = "除外のきっかけになった音楽ジャンルID")]
+    pub source_music_genre_id: Option<u32>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ExcludedArtistInput {
+    #[schemars(description = "アーティストID")]
+    pub id: String,
+    #[schemars(description = "アーティスト名")]
+    pub name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct InsertExcludedArtistsQuery {
+    #[schemars(description = "除外リストに登録するアーティストの配列")]
+    pub artists: Vec<ExcludedArtistInput>,
+    #[schemars(description = "除外した理由 (すべてのアーティストに設定します)")]
+    pub reason: Option<String>,
+    #[schemars(description = "除外の分類 (すべてのアーティストに設定します)")]
+    pub category: Option<ExclusionCategory>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DeleteExcludedArtistsQuery {
+    #[schemars(description = "除外リストから削除するアーティストIDの配列")]
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListExcludedArtistsQuery {
+    #[schemars(description = "取得件数 (デフォルト50、最大200)")]
+    pub limit: Option<u32>,
+    #[schemars(description = "取得開始位置 (デフォルト0)")]
+    pub offset: Option<u32>,
+    #[schemars(description = "登録日時の並び順 (asc または desc、デフォルトは desc)")]
+    pub order: Option<SortOrder>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchExcludedArtistsQuery {
+    #[schemars(description = "アーティスト名 (部分一致)")]
+    pub name: String,
+    #[schemars(description = "取得件数 (デフォルト50、最大200)")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CreateMusicGenreQuery {
+    #[schemars(description = "ジャンル名")]
+    pub name: String,
+    #[schemars(description = "Spotify の検索で使うジャンル (例: j-pop, hip hop)")]
+    pub search_key: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RenameMusicGenreQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub id: u32,
+    #[schemars(description = "新しいジャンル名")]
+    pub name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct UpdateMusicGenreSearchKeyQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub id: u32,
+    #[schemars(description = "新しい検索キー (例: j-pop, hip hop)")]
+    pub search_key: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DeleteMusicGenreQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub id: u32,
+    #[schemars(
+        description = "true の場合は音楽検索の進捗もあわせて削除します。false の場合は進捗があれば削除しません (デフォルト false)"
+    )]
+    pub cascade: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct HarvestMusicGenresQuery {
+    #[schemars(description = "フォロー中のアーティストのジャンルを集計するか (デフォルト true)")]
+    pub include_followed: Option<bool>,
+    #[schemars(description = "検索結果のジャンルを集計する検索キーの配列 (例: j-pop, hip hop)")]
+    pub search_keys: Option<Vec<String>>,
+    #[schemars(description = "検索キーごとに集計するアーティスト数 (デフォルト50、最大1000)")]
+    pub search_limit: Option<u32>,
+    #[schemars(
+        description = "true の場合は未登録のジャンルを音楽ジャンルとして登録します (デフォルト false)"
+    )]
+    pub insert: Option<bool>,
+    #[schemars(description = "登録するジャンルの最低出現数 (デフォルト1)")]
+    pub min_count: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct GenreCount {
+    genre: String,
+    count: u32,
+    registered: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DiscoverQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub music_genre_id: u32,
+    #[schemars(description = "取得する候補のアーティスト数 (デフォルト10、最大50)")]
+    pub count: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct DiscoverResult {
+    music_genre_id: u32,
+    search_key: String,
+    previous_position: u32,
+    position: u32,
+    scanned: u32,
+    skipped_following: u32,
+    skipped_excluded: u32,
+    total: Option<u32>,
+    exhausted: bool,
+    candidates: Vec<Candidate>,
+}
+
+#[derive(Serialize)]
+pub struct Candidate {
+    id: String,
+    name: String,
+    genres: Vec<String>,
+    popularity: u32,
+    uri: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct MusicSearchProgressQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub music_genre_id: u32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct InsertMusicSearchProgressQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub music_genre_id: u32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct UpdateMusicSearchProgressQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub music_genre_id: u32,
+    #[schemars(description = "実際に確認した件数 (デフォルト10)")]
+    pub consumed: Option<u32>,
+    #[schemars(
+        description = "読み込んだときの検索位置。指定した場合は現在の位置と一致するときだけ進めます"
+    )]
+    pub expected_position: Option<u32>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ResetMusicSearchProgressQuery {
+    #[schemars(description = "音楽ジャンルID")]
+    pub music_genre_id: u32,
+    #[schemars(description = "やり直す検索位置 (デフォルト0)")]
+    pub position: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct ArtistSearch {
+    repositories: Repositories,
+    spotify: SpotifyClient,
+    preferred_device: Option<String>,
+}
+
+#[tool(tool_box)]
+impl ArtistSearch {
+    pub fn new(config: &Config, repositories: Repositories) -> Result<Self> {
+        Ok(Self {
+            repositories,
+            spotify: SpotifyClient::new(&config.spotify)?,
+            preferred_device: config.spotify.preferred_device.clone(),
+        })
+    }
+
+    pub fn spotify(&self) -> &SpotifyClient {
+        &self.spotify
+    }
+
+    #[tool(description = "アーティストを検索します")]
+    pub async fn search(
+        &self,
+        #[tool(aggr)] SearchQuery { genre, position }: SearchQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let query = spotify::v1::search::artist::GetQuery {
+            offset: Some(position),
+            limit: Some(music_search::FETCH_LIMIT),
+            genre: Some(genre.clone()),
+        };
+        let artists = match self.spotify.search_artists(&query).await {
+            Ok(response) => response.artists.items,
+            Err(e) => {
+                return Err(spotify_error("アーティストの検索に失敗しました", e));
+            }
+        };
+        let output = if artists.is_empty() {
+            format!(
+                "ジャンル '{}' に一致するアーティストが見つかりませんでした。",
+                genre
+            )
+        } else {
+            let mut output = format!("ジャンル '{}' の検索結果:\n\n", genre);
+            for artist in artists {
+                output.push_str(&format!(
+                    "アーティストID: {}\nアーティスト名: {}\nジャンル: {}\nURI: {}\n",
+                    artist.id,
+                    artist.name,
+                    artist.genres.join(","),
+                    artist.uri
+                ));
+            }
+
+            output
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "アーティストをフォローしているか判定します")]
+    pub async fn is_following(
+        &self,
+        #[tool(aggr)] IsFollowingQuery { ids }: IsFollowingQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let (following, artists) = futures::try_join!(
+            self.spotify
+                .check_following(spotify::v1::me::following::contains::Type::Artist, &ids),
+            self.spotify.get_artists(&ids),
+        )
+        .map_err(|e| spotify_error("フォロー状況の取得に失敗しました", e))?;
+        let statuses = ids
+            .into_iter()
+            .zip(following)
+            .zip(artists)
+            .map(|((id, following), artist)| FollowStatus {
+                id,
+                name: artist.map(|artist| artist.name),
+                following,
+            })
+            .collect::<Vec<_>>();
+        let mut output = String::from("アーティストのフォロー状況:\n");
+        for status in &statuses {
+            output.push_str(&format!(
+                "アーティストID: {}\nアーティスト名: {}\nフォロー状況: {}\n",
+                status.id,
+                status.name.as_deref().unwrap_or("不明"),
+                match status.following {
+                    true => "フォロー済み",
+                    false => "未フォロー",
+                }
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![
+            Content::text(output),
+            Content::json(&statuses)?,
+        ]))
+    }
+
+    #[tool(
+        description = "曲を再生します。context_uri でアルバム・アーティスト・プレイリストを、uris で曲を指定します"
+    )]
+    pub async fn play(
+        &self,
+        #[tool(aggr)] PlayQuery {
+            context_uri,
+            uris,
+            offset_position,
+            offset_uri,
+            position_ms,
+            device_id,
+            auto_select_device,
+        }: PlayQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let invalid_params =
+            |message: &str| McpError::new(ErrorCode::INVALID_PARAMS, message.to_string(), None);
+        if context_uri.is_some() == uris.is_some() {
+            return Err(invalid_params(
+                "context_uri と uris のどちらか一方を指定してください",
+            ));
+        }
+        let offset = match (offset_position, offset_uri) {
+            (Some(_), Some(_)) => {
+                return Err(invalid_params(
+                    "offset_position と offset_uri は同時に指定できません",
+                ));
+            }
+            (Some(position), None) => Some(Offset::Position(position)),
+            (None, Some(uri)) => Some(Offset::Uri(uri)),
+            (None, None) => None,
+        };
+        let mut device_id = device_id;
+        let mut selected_device = None;
+        if device_id.is_none() && auto_select_device.unwrap_or(false) {
+            let devices = self
+                .spotify
+                .get_devices()
+                .await
+                .map_err(|e| spotify_error("デバイス一覧の取得に失敗しました", e))?;
+            if !devices.iter().any(|device| device.is_active) {
+                let device =
+                    select_device(&devices, self.preferred_device.as_deref()).ok_or_else(|| {
+                        McpError::new(
+                            ErrorCode::RESOURCE_NOT_FOUND,
+                            "操作できるデバイスがありません。Spotify アプリを起動してください",
+                            None,
+                        )
+                    })?;
+                device_id = device.id.clone();
+                selected_device = Some(device.name.clone());
+            }
+        }
+        let body = PutBody {
+            context_uri,
+            uris,
+            offset,
+            position_ms,
+        };
+        self.spotify
+            .play(&body, device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("曲の再生に失敗しました", e))?;
+        let output = match selected_device {
+            Some(name) => format!("デバイス '{}' で曲を再生しました", name),
+            None => "曲を再生しました".to_string(),
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "再生に使えるデバイスの一覧を取得します")]
+    pub async fn get_devices(&self) -> Result<CallToolResult, McpError> {
+        let devices = self
+            .spotify
+            .get_devices()
+            .await
+            .map_err(|e| spotify_error("デバイス一覧の取得に失敗しました", e))?;
+        let mut output = String::from("デバイス:\n");
+        if devices.is_empty() {
+            output.push_str("利用できるデバイスがありません。Spotify アプリを起動してください\n");
+        }
+        for device in &devices {
+            output.push_str(&format!(
+                "デバイスID: {}\nデバイス名: {}\n種類: {}\n音量: {}\n状態: {}{}\n\n",
+                device.id.as_deref().unwrap_or("不明"),
+                device.name,
+                device.device_type,
+                device
+                    .volume_percent
+                    .map(|volume| format!("{}%", volume))
+                    .unwrap_or_else(|| "不明".to_string()),
+                match device.is_active {
+                    true => "アクティブ",
+                    false => "非アクティブ",
+                },
+                match device.is_restricted {
+                    true => " (操作できません)",
+                    false => "",
+                }
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![
+            Content::text(output),
+            Content::json(&devices)?,
+        ]))
+    }
+
+    #[tool(description = "再生するデバイスを切り替えます")]
+    pub async fn transfer_playback(
+        &self,
+        #[tool(aggr)] TransferPlaybackQuery { device_id, play }: TransferPlaybackQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .transfer_playback(&device_id, play.unwrap_or(false))
+            .await
+            .map_err(|e| spotify_error("再生するデバイスの切り替えに失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "再生するデバイスを切り替えました\nデバイスID: {}",
+            device_id
+        ))]))
+    }
+
+    #[tool(description = "一時停止中の再生を再開します")]
+    pub async fn resume(
+        &self,
+        #[tool(aggr)] DeviceQuery { device_id }: DeviceQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .resume(device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("再生の再開に失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "再生を再開しました",
+        )]))
+    }
+
+    #[tool(description = "再生を一時停止します")]
+    pub async fn pause(
+        &self,
+        #[tool(aggr)] DeviceQuery { device_id }: DeviceQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .pause(device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("再生の一時停止に失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "再生を一時停止しました",
+        )]))
+    }
+
+    #[tool(description = "次の曲にスキップします")]
+    pub async fn skip_to_next(
+        &self,
+        #[tool(aggr)] DeviceQuery { device_id }: DeviceQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .skip_to_next(device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("次の曲へのスキップに失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "次の曲にスキップしました",
+        )]))
+    }
+
+    #[tool(description = "前の曲に戻ります")]
+    pub async fn skip_to_previous(
+        &self,
+        #[tool(aggr)] DeviceQuery { device_id }: DeviceQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .skip_to_previous(device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("前の曲に戻るのに失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "前の曲に戻りました",
+        )]))
+    }
+
+    #[tool(description = "再生中の曲の再生位置を移動します")]
+    pub async fn seek(
+        &self,
+        #[tool(aggr)] SeekQuery {
+            position_ms,
+            device_id,
+        }: SeekQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .seek(position_ms, device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("再生位置の移動に失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "再生位置を{}ミリ秒に移動しました",
+            position_ms
+        ))]))
+    }
+
+    #[tool(description = "音量を設定します")]
+    pub async fn set_volume(
+        &self,
+        #[tool(aggr)] SetVolumeQuery {
+            volume_percent,
+            device_id,
+        }: SetVolumeQuery,
+    ) -> Result<CallToolResult, McpError> {
+        if volume_percent > constant::spotify::MAX_VOLUME_PERCENT {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "音量は0から{}の範囲で指定してください: {}",
+                    constant::spotify::MAX_VOLUME_PERCENT,
+                    volume_percent
+                ),
+                None,
+            ));
+        }
+        self.spotify
+            .set_volume(volume_percent, device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("音量の設定に失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "音量を{}%に設定しました",
+            volume_percent
+        ))]))
+    }
+
+    #[tool(description = "シャッフル再生のオン・オフを切り替えます")]
+    pub async fn set_shuffle(
+        &self,
+        #[tool(aggr)] SetShuffleQuery { state, device_id }: SetShuffleQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .set_shuffle(state, device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("シャッフルの設定に失敗しました", e))?;
+        let output = match state {
+            true => "シャッフルをオンにしました",
+            false => "シャッフルをオフにしました",
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "リピート再生の種類を設定します")]
+    pub async fn set_repeat(
+        &self,
+        #[tool(aggr)] SetRepeatQuery { state, device_id }: SetRepeatQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.spotify
+            .set_repeat(state, device_id.as_deref())
+            .await
+            .map_err(|e| spotify_error("リピートの設定に失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "リピートを{}に設定しました",
+            state.description()
+        ))]))
+    }
+
+    #[tool(description = "アーティストをフォローします")]
+    pub async fn follow(
+        &self,
+        #[tool(aggr)] FollowQuery { ids }: FollowQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let response = self
+            .spotify
+            .follow(spotify::v1::me::following::PutType::Artist, &ids)
+            .await;
+        let output = match response {
+            Ok(_) => "アーティストをフォローしました",
+            Err(e) => return Err(spotify_error("アーティストのフォローに失敗しました", e)),
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Spotify API のレートリミッターの状態を取得します")]
+    pub async fn get_rate_limiter_status(&self) -> Result<CallToolResult, McpError> {
+        let state = self.spotify.rate_limiter().state().await;
+        let output = format!(
+            "レートリミッターの状態:\nバースト上限: {}\n毎秒の補充数: {}\n利用可能なトークン: {:.2}\n待機中のリクエスト: {}\n累計リクエスト数: {}\n待機が発生したリクエスト数: {}",
+            state.burst,
+            state.requests_per_second,
+            state.available_tokens,
+            state.waiting,
+            state.acquired,
+            state.throttled,
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "除外されているアーティストのリストを取得します")]
+    pub async fn get_excluded_artists_by_ids(
+        &self,
+        #[tool(aggr)] GetExcludedArtistsByIdsQuery { ids }: GetExcludedArtistsByIdsQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let excluded_artists = self.repositories.excluded_artists.find_by_ids(&ids).await;
+        let mut output = String::from("除外されているアーティスト:\n");
+        match excluded_artists {
+            Ok(excluded_artists) => {
+                for excluded_artist in &excluded_artists {
+                    output.push_str(&describe_excluded_artist(excluded_artist));
+                }
+            }
+            Err(e) => {
+                return Err(McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("除外されているアーティストの取得に失敗しました ,{}", e),
+                    None,
+                ));
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "アーティストIDの配列から除外されていないものを取得します")]
+    pub async fn filter_not_excluded_artists(
+        &self,
+        #[tool(aggr)] FilterNotExcludedArtistsQuery { ids }: FilterNotExcludedArtistsQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let ids = self
+            .repositories
+            .excluded_artists
+            .filter_not_excluded(&ids)
+            .await;
+        match ids {
+            Ok(ids) => {
+                let mut output = String::from("除外されていないアーティストID:\n");
+                for id in &ids {
+                    output.push_str(&format!("{}\n", id));
+                }
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("除外されていないアーティストの取得に失敗しました,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "アーティストを除外リストに登録します")]
+    pub async fn insert_excluded_artist(
+        &self,
+        #[tool(aggr)] InsertExcludedArtistQuery {
+            id,
+            name,
+            reason,
+            category,
+            expires_at,
+            source_music_genre_id,
+        }: InsertExcludedArtistQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let expires_at = expires_at.as_deref().map(parse_datetime).transpose()?;
+        let input = InsertInput::new(
+            id,
+            name,
+            reason,
+            category,
+            expires_at,
+            source_music_genre_id,
+        );
+        let excluded_artist = self.repositories.excluded_artists.insert(&input).await;
+        let output = match excluded_artist {
+            Ok(_) => "アーティストを除外リストに登録しました",
+            Err(e) => {
+                return Err(McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("アーティストを除外リストに登録するのに失敗しました ,{}", e),
+                    None,
+                ));
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "複数のアーティストを除外リストに登録します。登録済みのアーティストは無視します"
+    )]
+    pub async fn insert_excluded_artists(
+        &self,
+        #[tool(aggr)] InsertExcludedArtistsQuery {
+            artists,
+            reason,
+            category,
+        }: InsertExcludedArtistsQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let inputs = artists
+            .into_iter()
+            .map(|artist| {
+                InsertInput::new(artist.id, artist.name, reason.clone(), category, None, None)
+            })
+            .collect::<Vec<_>>();
+        let inserted = self
+            .repositories
+            .excluded_artists
+            .bulk_insert(&inputs)
+            .await;
+        match inserted {
+            Ok(inserted) => {
+                let output = format!(
+                    "アーティストを除外リストに登録しました\n新規登録: {}件\n登録済み: {}件",
+                    inserted,
+                    inputs.len() as u64 - inserted,
+                );
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("アーティストを除外リストに登録するのに失敗しました ,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "アーティストを除外リストから削除します")]
+    pub async fn delete_excluded_artists(
+        &self,
+        #[tool(aggr)] DeleteExcludedArtistsQuery { ids }: DeleteExcludedArtistsQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let deleted = self.repositories.excluded_artists.delete_by_ids(&ids).await;
+        match deleted {
+            Ok(deleted) => {
+                let output = format!(
+                    "アーティストを除外リストから削除しました\n削除件数: {}件",
+                    deleted
+                );
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "アーティストを除外リストから削除するのに失敗しました ,{}",
+                    e
+                ),
+                None,
+            )),
+        }
+    }
+
+    #[tool(
+        description = "除外リストのアーティストを登録日時順に一覧で取得します。除外期限が切れたものは含みません"
+    )]
+    pub async fn list_excluded_artists(
+        &self,
+        #[tool(aggr)] ListExcludedArtistsQuery {
+            limit,
+            offset,
+            order,
+        }: ListExcludedArtistsQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let input = ListInput::new(
+            limit
+                .unwrap_or(excluded_artist::DEFAULT_LIST_LIMIT)
+                .min(excluded_artist::MAX_LIST_LIMIT),
+            offset.unwrap_or(0),
+            order.unwrap_or_default(),
+        );
+        let result = futures::try_join!(
+            self.repositories.excluded_artists.find_all(&input),
+            self.repositories.excluded_artists.count(),
+        );
+        match result {
+            Ok((excluded_artists, count)) => {
+                let mut output = format!("除外されているアーティスト (全{}件):\n", count);
+                for excluded_artist in &excluded_artists {
+                    output.push_str(&describe_excluded_artist(excluded_artist));
+                }
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("除外されているアーティストの取得に失敗しました ,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "除外リストのアーティストを名前で検索します")]
+    pub async fn search_excluded_artists(
+        &self,
+        #[tool(aggr)] SearchExcludedArtistsQuery { name, limit }: SearchExcludedArtistsQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let limit = limit
+            .unwrap_or(excluded_artist::DEFAULT_LIST_LIMIT)
+            .min(excluded_artist::MAX_LIST_LIMIT);
+        let excluded_artists = self
+            .repositories
+            .excluded_artists
+            .search_by_name(&name, limit)
+            .await;
+        match excluded_artists {
+            Ok(excluded_artists) => {
+                let mut output = format!("'{}' に一致する除外されているアーティスト:\n", name);
+                for excluded_artist in &excluded_artists {
+                    output.push_str(&describe_excluded_artist(excluded_artist));
+                }
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("除外されているアーティストの検索に失敗しました ,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "音楽ジャンル一覧を取得します")]
+    pub async fn get_music_genres(&self) -> Result<CallToolResult, McpError> {
+        let genres = self.repositories.music_genres.find_all().await;
+        let mut output = String::from("音楽ジャンル:\n");
+        match genres {
+            Ok(genres) => {
+                for genre in genres {
+                    output.push_str(&format!(
+                        "ID: {}\nジャンル名: {}\n検索キー: {}\n\n",
+                        genre.id, genre.name, genre.search_key
+                    ));
+                }
+            }
+            Err(e) => {
+                return Err(McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("音楽ジャンル一覧の取得に失敗しました,{}", e),
+                    None,
+                ));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "音楽ジャンルを登録します")]
+    pub async fn create_music_genre(
+        &self,
+        #[tool(aggr)] CreateMusicGenreQuery { name, search_key }: CreateMusicGenreQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let input = music_genre::InsertInput::new(name, search_key);
+        let genre = self
+            .repositories
+            .music_genres
+            .insert(&input)
+            .await
+            .map_err(|e| music_genre_error("音楽ジャンルの登録に失敗しました", e))?;
+        let output = format!(
+            "音楽ジャンルを登録しました\nID: {}\nジャンル名: {}\n検索キー: {}",
+            genre.id, genre.name, genre.search_key
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "音楽ジャンルの名前を変更します")]
+    pub async fn rename_music_genre(
+        &self,
+        #[tool(aggr)] RenameMusicGenreQuery { id, name }: RenameMusicGenreQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let genre = self
+            .repositories
+            .music_genres
+            .update_name(id, &name)
+            .await
+            .map_err(|e| music_genre_error("音楽ジャンルの名前の変更に失敗しました", e))?;
+        let output = format!(
+            "音楽ジャンルの名前を変更しました\nID: {}\nジャンル名: {}\n検索キー: {}",
+            genre.id, genre.name, genre.search_key
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "音楽ジャンルの検索キーを変更します")]
+    pub async fn update_music_genre_search_key(
+        &self,
+        #[tool(aggr)]
+        UpdateMusicGenreSearchKeyQuery { id, search_key }: UpdateMusicGenreSearchKeyQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let genre = self
+            .repositories
+            .music_genres
+            .update_search_key(id, &search_key)
+            .await
+            .map_err(|e| music_genre_error("音楽ジャンルの検索キーの変更に失敗しました", e))?;
+        let output = format!(
+            "音楽ジャンルの検索キーを変更しました\nID: {}\nジャンル名: {}\n検索キー: {}",
+            genre.id, genre.name, genre.search_key
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "音楽ジャンルを削除します")]
+    pub async fn delete_music_genre(
+        &self,
+        #[tool(aggr)] DeleteMusicGenreQuery { id, cascade }: DeleteMusicGenreQuery,
+    ) -> Result<CallToolResult, McpError> {
+        self.repositories
+            .music_genres
+            .delete(id, cascade.unwrap_or(false))
+            .await
+            .map_err(|e| music_genre_error("音楽ジャンルの削除に失敗しました", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "音楽ジャンルを削除しました\nID: {}",
+            id
+        ))]))
+    }
+
+    #[tool(
+        description = "フォロー中のアーティストと検索結果からジャンルを出現数つきで集計し、未登録のジャンルを音楽ジャンルとして登録します"
+    )]
+    pub async fn harvest_music_genres(
+        &self,
+        #[tool(aggr)] HarvestMusicGenresQuery {
+            include_followed,
+            search_keys,
+            search_limit,
+            insert,
+            min_count,
+        }: HarvestMusicGenresQuery,
+    ) -> Result<CallToolResult, McpError> {
+        // 同じアーティストを重複して数えないようにIDごとに集める
+        let mut artists = HashMap::new();
+        if include_followed.unwrap_or(true) {
+            let followed =
+                self.spotify.get_followed_artists().await.map_err(|e| {
+                    spotify_error("フォロー中のアーティストの取得に失敗しました", e)
+                })?;
+            for artist in followed {
+                artists.insert(artist.id, artist.genres);
+            }
+        }
+        let search_limit = search_limit
+            .unwrap_or(constant::music_genre::DEFAULT_HARVEST_SEARCH_LIMIT)
+            .min(constant::music_genre::MAX_HARVEST_SEARCH_LIMIT);
+        for search_key in search_keys.unwrap_or_default() {
+            let found = self
+                .spotify
+                .search_artists_stream(&search_key, 0, Some(search_limit as usize))
+                .try_collect::<Vec<_>>()
+                .await
+                .map_err(|e| spotify_error("アーティストの検索に失敗しました", e))?;
+            for artist in found {
+                artists.insert(artist.id, artist.genres);
+            }
+        }
+
+        let mut counts = HashMap::<String, u32>::new();
+        for genre in artists.values().flatten() {
+            *counts.entry(genre.clone()).or_default() += 1;
+        }
+        let registered = self
+            .repositories
+            .music_genres
+            .find_all()
+            .await
+            .map_err(|e| music_genre_error("音楽ジャンル一覧の取得に失敗しました", e))?
+            .into_iter()
+            .map(|genre| genre.search_key)
+            .collect::<HashSet<_>>();
+        let mut genres = counts
+            .into_iter()
+            .map(|(genre, count)| GenreCount {
+                registered: registered.contains(&genre),
+                genre,
+                count,
+            })
+            .collect::<Vec<_>>();
+        genres.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.genre.cmp(&b.genre)));
+
+        let mut output = format!(
+            "{}組のアーティストから{}個のジャンルを集計しました:\n",
+            artists.len(),
+            genres.len()
+        );
+        for genre in &genres {
+            output.push_str(&format!(
+                "{} ({}件){}\n",
+                genre.genre,
+                genre.count,
+                if genre.registered {
+                    " 登録済み"
+                } else {
+                    ""
+                }
+            ));
+        }
+        if insert.unwrap_or(false) {
+            // 検索キーとして使えない文字列は登録しない
+            let inputs = genres
+                .iter()
+                .filter(|genre| !genre.registered && genre.count >= min_count.unwrap_or(1))
+                .filter(|genre| music_genre::validate_search_key(&genre.genre).is_ok())
+                .map(|genre| {
+                    music_genre::InsertInput::new(genre.genre.clone(), genre.genre.clone())
+                })
+                .collect::<Vec<_>>();
+            let inserted = self
+                .repositories
+                .music_genres
+                .bulk_insert(&inputs)
+                .await
+                .map_err(|e| music_genre_error("音楽ジャンルの登録に失敗しました", e))?;
+            output.push_str(&format!("\n未登録のジャンルを{}件登録しました", inserted));
+        } else {
+            output.push_str("\n未登録のジャンルを登録する場合は insert に true を指定してください");
+        }
+
+        Ok(CallToolResult::success(vec![
+            Content::text(output),
+            Content::json(&genres)?,
+        ]))
+    }
+
+    #[tool(
+        description = "音楽ジャンルの検索の続きから、フォロー中・除外済みのアーティストを除いた候補を取得し、検索の進捗を進めます"
+    )]
+    pub async fn discover(
+        &self,
+        #[tool(aggr)] DiscoverQuery {
+            music_genre_id,
+            count,
+        }: DiscoverQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let count = count
+            .unwrap_or(music_search::DEFAULT_DISCOVER_COUNT)
+            .clamp(1, music_search::MAX_DISCOVER_COUNT) as usize;
+        let genre = self
+            .repositories
+            .music_genres
+            .find_by_id(music_genre_id)
+            .await
+            .map_err(|e| music_genre_error("音楽ジャンルの取得に失敗しました", e))?
+            .ok_or_else(|| {
+                music_genre_error(
+                    "音楽ジャンルの取得に失敗しました",
+                    MusicGenreError::NotFound(music_genre_id).into(),
+                )
+            })?;
+        let progress = self
+            .repositories
+            .music_search_progresses
+            .find_or_create(music_genre_id)
+            .await
+            .map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("音楽検索の進捗の取得に失敗しました,{}", e),
+                    None,
+                )
+            })?;
+
+        let mut result = DiscoverResult {
+            music_genre_id,
+            search_key: genre.search_key,
+            previous_position: progress.position,
+            position: progress.position,
+            scanned: 0,
+            skipped_following: 0,
+            skipped_excluded: 0,
+            total: progress.last_total,
+            exhausted: progress.is_completed(),
+            candidates: Vec::new(),
+        };
+        'pages: while !result.exhausted && result.candidates.len() < count {
+            let remaining = constant::spotify::SEARCH_OFFSET_LIMIT.saturating_sub(result.position);
+            if remaining == 0 {
+                result.exhausted = true;
+                break;
+            }
+            if result.scanned >= music_search::MAX_DISCOVER_SCAN {
+                break;
+            }
+            let query = spotify::v1::search::artist::GetQuery {
+                offset: Some(result.position),
+                limit: Some(remaining.min(constant::spotify::MAX_PAGE_LIMIT)),
+                genre: Some(result.search_key.clone()),
+            };
+            let page = self
+                .spotify
+                .search_artists(&query)
+                .await
+                .map_err(|e| spotify_error("アーティストの検索に失敗しました", e))?
+                .artists;
+            result.total = Some(page.total);
+            if page.items.is_empty() {
+                result.exhausted = true;
+                break;
+            }
+            let has_next = page.next.is_some();
+
+            let ids = page
+                .items
+                .iter()
+                .map(|artist| artist.id.clone())
+                .collect::<Vec<_>>();
+            let (following, not_excluded) = futures::try_join!(
+                async {
+                    self.spotify
+                        .check_following(spotify::v1::me::following::contains::Type::Artist, &ids)
+                        .await
+                        .map_err(|e| spotify_error("フォロー状況の取得に失敗しました", e))
+                },
+                async {
+                    self.repositories
+                        .excluded_artists
+                        .filter_not_excluded(&ids)
+                        .await
+                        .map_err(|e| {
+                            McpError::new(
+                                ErrorCode::INTERNAL_ERROR,
+                                format!("除外されていないアーティストの取得に失敗しました,{}", e),
+                                None,
+                            )
+                        })
+                },
+            )?;
+            let not_excluded = not_excluded.into_iter().collect::<HashSet<_>>();
+
+            // 候補が揃った時点で止め、残りは次回に回せるよう実際に見た件数だけ進める
+            for (artist, following) in page.items.into_iter().zip(following) {
+                result.position += 1;
+                result.scanned += 1;
+                if following {
+                    result.skipped_following += 1;
+                } else if !not_excluded.contains(&artist.id) {
+                    result.skipped_excluded += 1;
+                } else {
+                    result.candidates.push(Candidate {
+                        id: artist.id,
+                        name: artist.name,
+                        genres: artist.genres,
+                        popularity: artist.popularity,
+                        uri: artist.uri,
+                    });
+                    if result.candidates.len() >= count {
+                        break 'pages;
+                    }
+                }
+            }
+            if !has_next {
+                result.exhausted = true;
+                break;
+            }
+        }
+
+        let update_error = |e: anyhow::Error| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("音楽検索の進捗の更新に失敗しました,{}", e),
+                None,
+            )
+        };
+        if let Some(total) = result.total {
+            self.repositories
+                .music_search_progresses
+                .record_total(music_genre_id, total)
+                .await
+                .map_err(update_error)?;
+        }
+        let advanced = self
+            .repositories
+            .music_search_progresses
+            .advance(
+                music_genre_id,
+                Some(result.previous_position),
+                result.scanned,
+            )
+            .await
+            .map_err(update_error)?;
+        if advanced.is_none() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_REQUEST,
+                "他の処理が同時に音楽検索の進捗を更新したため、進捗を進めませんでした。もう一度実行してください",
+                None,
+            ));
+        }
+        if result.exhausted {
+            self.repositories
+                .music_search_progresses
+                .complete(music_genre_id)
+                .await
+                .map_err(update_error)?;
+        }
+
+        let mut output = format!(
+            "ジャンル '{}' の候補 ({}件):\n\n",
+            result.search_key,
+            result.candidates.len()
+        );
+        for candidate in &result.candidates {
+            output.push_str(&format!(
+                "アーティストID: {}\nアーティスト名: {}\nジャンル: {}\nURI: {}\n\n",
+                candidate.id,
+                candidate.name,
+                candidate.genres.join(","),
+                candidate.uri
+            ));
+        }
+        output.push_str(&format!(
+            "検索位置: {} -> {}\n確認した件数: {}件 (フォロー中: {}件、除外済み: {}件)",
+            result.previous_position,
+            result.position,
+            result.scanned,
+            result.skipped_following,
+            result.skipped_excluded
+        ));
+        if result.exhausted {
+            output.push_str("\nこのジャンルの検索結果はこれ以上ありません");
+        }
+
+        Ok(CallToolResult::success(vec![
+            Content::text(output),
+            Content::json(&result)?,
+        ]))
+    }
+
+    #[tool(description = "音楽検索の進捗を取得します")]
+    pub async fn get_music_search_progress(
+        &self,
+        #[tool(aggr)] MusicSearchProgressQuery { music_genre_id }: MusicSearchProgressQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = self
+            .repositories
+            .music_search_progresses
+            .find_by_music_genre_id(music_genre_id)
+            .await;
+        match progress {
+            Ok(progress) => {
+                let output = match progress {
+                    Some(progress) => describe_progress(&progress),
+                    None => "音楽検索の進捗が見つかりません".to_string(),
+                };
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("音楽検索の進捗の取得に失敗しました,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "音楽検索の進捗を登録します")]
+    pub async fn insert_music_search_progress(
+        &self,
+        #[tool(aggr)]
+        InsertMusicSearchProgressQuery { music_genre_id }: InsertMusicSearchProgressQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let input = music_search_progress::UpsertInput::new(music_search::INITIAL_POSITION);
+        let progress = self
+            .repositories
+            .music_search_progresses
+            .upsert(music_genre_id, &input)
+            .await;
+        match progress {
+            Ok(progress) => {
+                let output = format!(
+                    "音楽検索の進捗を登録しました\n{}",
+                    describe_progress(&progress)
+                );
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("音楽検索の進捗の登録に失敗しました,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "音楽検索の進捗を確認した件数だけ進めます")]
+    pub async fn update_music_search_progress(
+        &self,
+        #[tool(aggr)] UpdateMusicSearchProgressQuery {
+            music_genre_id,
+            consumed,
+            expected_position,
+        }: UpdateMusicSearchProgressQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let consumed = consumed.unwrap_or(music_search::FETCH_LIMIT);
+        let progress = self
+            .repositories
+            .music_search_progresses
+            .advance(music_genre_id, expected_position, consumed)
+            .await;
+        match progress {
+            Ok(Some(progress)) => {
+                let output = format!(
+                    "音楽検索の進捗を更新しました\n{}",
+                    describe_progress(&progress)
+                );
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Ok(None) => {
+                let current = self
+                    .repositories
+                    .music_search_progresses
+                    .find_by_music_genre_id(music_genre_id)
+                    .await;
+                match current {
+                    Ok(Some(progress)) => Err(McpError::new(
+                        ErrorCode::INVALID_REQUEST,
+                        format!(
+                            "他の処理が先に音楽検索の進捗を更新したため、進捗を進めませんでした\n{}",
+                            describe_progress(&progress)
+                        ),
+                        None,
+                    )),
+                    Ok(None) => Ok(CallToolResult::success(vec![Content::text(
+                        "音楽検索の進捗が見つかりません",
+                    )])),
+                    Err(e) => Err(McpError::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("音楽検索の進捗の取得に失敗しました,{}", e),
+                        None,
+                    )),
+                }
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("音楽検索の進捗の更新に失敗しました,{}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(
+        description = "音楽検索の進捗を指定した位置 (デフォルトは先頭) に戻し、完了状態を解除して検索をやり直せるようにします"
+    )]
+    pub async fn reset_music_search_progress(
+        &self,
+        #[tool(aggr)] ResetMusicSearchProgressQuery {
+            music_genre_id,
+            position,
+        }: ResetMusicSearchProgressQuery,
+    ) -> Result<CallToolResult, McpError> {
+        let position = position
+            .unwrap_or(music_search::INITIAL_POSITION)
+            .min(constant::spotify::SEARCH_OFFSET_LIMIT);
+        let progress = self
+            .repositories
+            .music_search_progresses
+            .reset(music_genre_id, position)
+            .await;
+        match progress {
+            Ok(progress) => {
+                let output = format!(
+                    "音楽検索の進捗をリセットしました\n{}",
+                    describe_progress(&progress)
+                );
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Err(McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("音楽検索の進捗のリセットに失敗しました,{}", e),
+                None,
+            )),
+        }
+    }
+}
+
+#[tool(tool_box)]
+impl ServerHandler for ArtistSearch {}
+
+fn describe_progress(progress: &MusicSearchProgress) -> String {
+    let mut output = format!(
+        "音楽ジャンルID: {}\n現在の検索位置: {}",
+        progress.music_genre_id, progress.position,
+    );
+    if let Some(total) = progress.last_total {
+        output.push_str(&format!("\n検索結果の総数: {}", total));
+    }
+    if let Some(percent) = progress.percent_complete() {
+        output.push_str(&format!("\n進捗: {:.1}%", percent));
+    }
+    if let Some(completed_at) = progress.completed_at {
+        output.push_str(&format!("\n完了日時: {}", completed_at));
+    }
+
+    output
+}
+
+fn describe_excluded_artist(excluded_artist: &ExcludedArtist) -> String {
+    let mut output = format!(
+        "アーティストID: {}\nアーティスト名: {}\n",
+        excluded_artist.id, excluded_artist.name
+    );
+    if let Some(reason) = &excluded_artist.reason {
+        output.push_str(&format!("理由: {}\n", reason));
+    }
+    if let Some(category) = excluded_artist.category {
+        output.push_str(&format!("分類: {}\n", category.description()));
+    }
+    if let Some(expires_at) = excluded_artist.expires_at {
+        output.push_str(&format!("有効期限: {}\n", expires_at));
+    }
+    if let Some(source_music_genre_id) = excluded_artist.source_music_genre_id {
+        output.push_str(&format!(
+            "きっかけの音楽ジャンルID: {}\n",
+            source_music_genre_id
+        ));
+    }
+    output.push_str(&format!("登録日時: {}\n", excluded_artist.created_at));
+
+    output
+}
+
+fn parse_datetime(value: &str) -> Result<NaiveDateTime, McpError> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_err(|e| {
+            McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("日時の形式が正しくありません ({}),{}", value, e),
+                None,
+            )
+        })
+}
+
+fn music_genre_error(message: &str, e: anyhow::Error) -> McpError {
+    let code = match e.downcast_ref::<MusicGenreError>() {
+        Some(MusicGenreError::NotFound(_)) => ErrorCode::RESOURCE_NOT_FOUND,
+        Some(MusicGenreError::InvalidSearchKey { .. }) => ErrorCode::INVALID_PARAMS,
+        Some(MusicGenreError::DuplicateSearchKey(_) | MusicGenreError::Referenced(_)) => {
+            ErrorCode::INVALID_REQUEST
+        }
+        None => ErrorCode::INTERNAL_ERROR,
+    };
+
+    McpError::new(code, format!("{},{}", message, e), None)
+}
+
+// 操作できるデバイスのうち、名前かIDが優先するデバイスに一致するものを選び、なければ最初のものを選ぶ
+fn select_device<'a>(devices: &'a [Device], preferred: Option<&str>) -> Option<&'a Device> {
+    let mut candidates = devices
+        .iter()
+        .filter(|device| !device.is_restricted && device.id.is_some());
+    let preferred = preferred.and_then(|preferred| {
+        candidates.clone().find(|device| {
+            device.id.as_deref() == Some(preferred) || device.name.eq_ignore_ascii_case(preferred)
+        })
+    });
+
+    preferred.or_else(|| candidates.next())
+}
+
+fn spotify_error(message: &str, e: SpotifyError) -> McpError {
+    let code = match e {
+        SpotifyError::NotFound { .. } => ErrorCode::RESOURCE_NOT_FOUND,
+        SpotifyError::Unauthorized { .. }
+        | SpotifyError::Forbidden { .. }
+        | SpotifyError::Api { .. } => ErrorCode::INVALID_REQUEST,
+        _ => ErrorCode::INTERNAL_ERROR,
+    };
+    let data = serde_json::json!({
+        "status": e.status(),
+        "reason": e.reason(),
+    });
+
+    McpError::new(code, format!("{},{}", message, e), Some(data))
+}
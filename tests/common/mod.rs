@@ -0,0 +1,665 @@
+// Spotify API の代わりに使うローカルのHTTPサーバーと、それに向けた ArtistSearch を用意する
+#![allow(dead_code)]
+
+use rmcp::model::CallToolResult;
+use serde_json::{Value, json};
+use spotify_mcp::{
+    config::{
+        Config, DatabaseBackend, DatabaseConfig, RateLimitConfig, RetryConfig, SpotifyConfig,
+    },
+    repository::{Repositories, memory::MemoryRepository},
+    server::ArtistSearch,
+};
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+pub const REFRESH_TOKEN: &str = "test-refresh-token";
+pub const ACCESS_TOKEN: &str = "test-access-token";
+// 実際の API と同じく、1回に指定できるIDの数を制限する
+const MAX_IDS: usize = 50;
+
+#[derive(Clone)]
+pub struct MockArtist {
+    pub id: String,
+    pub name: String,
+    pub genres: Vec<String>,
+    pub popularity: u32,
+}
+
+pub fn artist(id: &str, name: &str, genres: &[&str]) -> MockArtist {
+    MockArtist {
+        id: id.to_string(),
+        name: name.to_string(),
+        genres: genres.iter().map(|genre| genre.to_string()).collect(),
+        popularity: 50,
+    }
+}
+
+// "rock" ジャンルのアーティストを ID 順に並ぶように作る
+pub fn rock_artists(count: usize) -> Vec<MockArtist> {
+    (0..count)
+        .map(|i| {
+            artist(
+                &format!("artist{:04}", i),
+                &format!("Artist {}", i),
+                &["rock"],
+            )
+        })
+        .collect()
+}
+
+impl MockArtist {
+    fn to_json(&self) -> Value {
+        json!({
+            "external_urls": { "spotify": format!("https://open.spotify.com/artist/{}", self.id) },
+            "followers": { "href": null, "total": 0 },
+            "genres": self.genres,
+            "href": format!("https://api.spotify.com/v1/artists/{}", self.id),
+            "id": self.id,
+            "images": [],
+            "name": self.name,
+            "popularity": self.popularity,
+            "type": "artist",
+            "uri": format!("spotify:artist:{}", self.id),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct MockDevice {
+    pub id: String,
+    pub name: String,
+    pub device_type: String,
+    pub is_active: bool,
+    pub is_restricted: bool,
+}
+
+pub fn device(id: &str, name: &str, device_type: &str) -> MockDevice {
+    MockDevice {
+        id: id.to_string(),
+        name: name.to_string(),
+        device_type: device_type.to_string(),
+        is_active: false,
+        is_restricted: false,
+    }
+}
+
+impl MockDevice {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "is_active": self.is_active,
+            "is_private_session": false,
+            "is_restricted": self.is_restricted,
+            "name": self.name,
+            "type": self.device_type,
+            "volume_percent": 50,
+            "supports_volume": true,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct MockPlayer {
+    pub is_playing: bool,
+    pub position_ms: u32,
+    pub volume_percent: u32,
+    pub shuffle: bool,
+    pub repeat: String,
+    // 次の曲・前の曲の操作で移動した曲数
+    pub skipped: i32,
+    // 最後に受け取った再生リクエストのボディ
+    pub played: Option<Value>,
+    // 最後の操作で指定されたデバイスID
+    pub device_id: Option<String>,
+}
+
+#[derive(Default)]
+pub struct SpotifyState {
+    pub artists: Vec<MockArtist>,
+    pub following: BTreeSet<String>,
+    pub devices: Vec<MockDevice>,
+    pub player: MockPlayer,
+    // 受け取ったリクエストを "GET /v1/search" の形式で記録する
+    pub requests: Vec<String>,
+    // 次の API リクエストから順に、1回ずつこのステータスのエラーを返す
+    pub failures: VecDeque<u16>,
+}
+
+pub struct MockSpotify {
+    pub base_url: String,
+    state: Arc<Mutex<SpotifyState>>,
+}
+
+impl MockSpotify {
+    pub async fn start(artists: Vec<MockArtist>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(SpotifyState {
+            artists,
+            devices: vec![MockDevice {
+                is_active: true,
+                ..device("d1", "Test Computer", "Computer")
+            }],
+            ..Default::default()
+        }));
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, server_state.clone()));
+            }
+        });
+
+        Self { base_url, state }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, SpotifyState> {
+        self.state.lock().unwrap()
+    }
+
+    pub fn config(&self) -> Config {
+        test_config(&self.base_url)
+    }
+
+    // "GET /v1/search" の形式で指定したリクエストを受け取った回数
+    pub fn request_count(&self, request: &str) -> usize {
+        self.state()
+            .requests
+            .iter()
+            .filter(|received| *received == request)
+            .count()
+    }
+}
+
+pub fn test_config(base_url: &str) -> Config {
+    Config {
+        spotify: SpotifyConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: Some("test-client-secret".to_string()),
+            refresh_token: Some(REFRESH_TOKEN.to_string()),
+            // 存在しないパスにして環境の認証情報ファイルを読まないようにする
+            credentials_path: std::env::temp_dir()
+                .join("spotify-mcp-test")
+                .join("missing")
+                .join("credentials.json"),
+            redirect_uri: "http://127.0.0.1:8888/callback".to_string(),
+            api_base_url: base_url.to_string(),
+            accounts_base_url: base_url.to_string(),
+            timeout: Duration::from_secs(5),
+            user_agent: "spotify-mcp-test".to_string(),
+            batch_concurrency: 2,
+            retry: RetryConfig {
+                max_retries: 0,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            },
+            rate_limit: RateLimitConfig {
+                burst: 1000,
+                requests_per_second: 1000.0,
+            },
+            preferred_device: None,
+            cassette: None,
+        },
+        database: DatabaseConfig {
+            backend: DatabaseBackend::Sqlite,
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+        },
+    }
+}
+
+pub struct TestContext {
+    pub spotify: MockSpotify,
+    pub server: ArtistSearch,
+}
+
+pub async fn setup(artists: Vec<MockArtist>) -> TestContext {
+    let spotify = MockSpotify::start(artists).await;
+    let server = artist_search(&spotify.config());
+
+    TestContext { spotify, server }
+}
+
+pub fn artist_search(config: &Config) -> ArtistSearch {
+    ArtistSearch::new(config, Repositories::new(MemoryRepository::new())).unwrap()
+}
+
+pub fn text(result: &CallToolResult) -> &str {
+    &result.content[0].as_text().unwrap().text
+}
+
+pub fn json(result: &CallToolResult) -> Value {
+    serde_json::from_str(&result.content[1].as_text().unwrap().text).unwrap()
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    body: Option<Value>,
+    retry_after: Option<u64>,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            body: Some(body),
+            retry_after: None,
+        }
+    }
+
+    fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: None,
+            retry_after: None,
+        }
+    }
+
+    fn error(status: u16, message: &str, reason: Option<&str>) -> Self {
+        Self {
+            status,
+            body: Some(json!({
+                "error": { "status": status, "message": message, "reason": reason }
+            })),
+            retry_after: None,
+        }
+    }
+}
+
+// reqwest はコネクションを使い回すため、1つの接続で複数のリクエストを処理する
+async fn serve_connection(stream: TcpStream, state: Arc<Mutex<SpotifyState>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    while let Some(request) = read_request(&mut reader).await {
+        let response = handle(&mut state.lock().unwrap(), request);
+        let body = response
+            .body
+            .map(|body| body.to_string())
+            .unwrap_or_default();
+        let retry_after = response
+            .retry_after
+            .map(|secs| format!("Retry-After: {}\r\n", secs))
+            .unwrap_or_default();
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}\r\n",
+            response.status,
+            reason_phrase(response.status),
+            body.len(),
+            retry_after
+        );
+        if writer
+            .write_all(format!("{}{}", head, body).as_bytes())
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+async fn read_request(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>) -> Option<Request> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).await.ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':')?;
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().ok()?,
+            "authorization" => authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await.ok()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query: parse_form(query),
+        authorization,
+        body,
+    })
+}
+
+fn handle(state: &mut SpotifyState, request: Request) -> Response {
+    state
+        .requests
+        .push(format!("{} {}", request.method, request.path));
+    if request.path == "/api/token" {
+        return token(&request);
+    }
+    if let Some(status) = state.failures.pop_front() {
+        return match status {
+            401 => Response::error(401, "The access token expired", None),
+            429 => Response {
+                retry_after: Some(0),
+                ..Response::error(429, "API rate limit exceeded", None)
+            },
+            status => Response::error(status, "Service unavailable", None),
+        };
+    }
+    if request.authorization.as_deref() != Some(&format!("Bearer {}", ACCESS_TOKEN)) {
+        return Response::error(401, "Invalid access token", None);
+    }
+
+    let segments = request.path.split('/').skip(1).collect::<Vec<_>>();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["v1", "search"]) => search(state, &request),
+        ("GET", ["v1", "me", "following"]) => followed_artists(state, &request),
+        ("PUT", ["v1", "me", "following"]) => {
+            let body = serde_json::from_slice::<Value>(&request.body).unwrap_or_default();
+            let ids = body["ids"].as_array().cloned().unwrap_or_default();
+            if ids.len() > MAX_IDS {
+                return too_many_ids();
+            }
+            for id in &ids {
+                state
+                    .following
+                    .insert(id.as_str().unwrap_or_default().to_string());
+            }
+            Response::no_content()
+        }
+        ("GET", ["v1", "me", "following", "contains"] | ["v1", "artists"])
+            if ids(&request).len() > MAX_IDS =>
+        {
+            too_many_ids()
+        }
+        ("GET", ["v1", "me", "following", "contains"]) => Response::ok(json!(
+            ids(&request)
+                .iter()
+                .map(|id| state.following.contains(id))
+                .collect::<Vec<_>>()
+        )),
+        ("GET", ["v1", "artists"]) => Response::ok(json!({
+            "artists": ids(&request)
+                .iter()
+                .map(|id| state.find_artist(id).map(MockArtist::to_json))
+                .collect::<Vec<_>>()
+        })),
+        ("GET", ["v1", "artists", id, "top-tracks"]) => match state.find_artist(id) {
+            Some(artist) => Response::ok(json!({
+                "tracks": [
+                    { "external_urls": { "spotify": format!("https://open.spotify.com/track/{}-1", artist.id) } }
+                ]
+            })),
+            None => Response::error(404, "Non existing id", None),
+        },
+        ("GET", ["v1", "me", "player", "devices"]) => Response::ok(json!({
+            "devices": state.devices.iter().map(MockDevice::to_json).collect::<Vec<_>>()
+        })),
+        ("PUT", ["v1", "me", "player"]) => {
+            let body = serde_json::from_slice::<Value>(&request.body).unwrap_or_default();
+            let Some(device_id) = body["device_ids"][0].as_str() else {
+                return Response::error(400, "Required parameter device_ids missing", None);
+            };
+            if !state.activate(device_id) {
+                return Response::error(404, "Device not found", None);
+            }
+            if body["play"].as_bool().unwrap_or(false) {
+                state.player.is_playing = true;
+            }
+            Response::no_content()
+        }
+        (method, ["v1", "me", "player", action]) => player(state, &request, method, action),
+        _ => Response::error(404, "Service not found", None),
+    }
+}
+
+impl SpotifyState {
+    fn activate(&mut self, device_id: &str) -> bool {
+        if !self.devices.iter().any(|device| device.id == device_id) {
+            return false;
+        }
+        for device in &mut self.devices {
+            device.is_active = device.id == device_id;
+        }
+        true
+    }
+
+    fn find_artist(&self, id: &str) -> Option<&MockArtist> {
+        self.artists.iter().find(|artist| artist.id == id)
+    }
+}
+
+fn player(state: &mut SpotifyState, request: &Request, method: &str, action: &str) -> Response {
+    // デバイスを指定した場合はそのデバイスに再生が移る
+    let device_id = request.query.get("device_id").cloned();
+    match &device_id {
+        Some(device_id) if !state.activate(device_id) => {
+            return Response::error(404, "Device not found", None);
+        }
+        None if !state.devices.iter().any(|device| device.is_active) => {
+            return Response::error(
+                404,
+                "Player command failed: No active device found",
+                Some("NO_ACTIVE_DEVICE"),
+            );
+        }
+        _ => {}
+    }
+    let player = &mut state.player;
+    match (method, action) {
+        ("PUT", "play") if request.body.is_empty() => player.is_playing = true,
+        ("PUT", "play") => {
+            let body = serde_json::from_slice::<Value>(&request.body).unwrap_or_default();
+            player.position_ms = body["position_ms"].as_u64().unwrap_or_default() as u32;
+            player.played = Some(body);
+            player.is_playing = true;
+        }
+        ("PUT", "pause") if !player.is_playing => {
+            return Response::error(
+                403,
+                "Player command failed: Restriction violated",
+                Some("ALREADY_PAUSED"),
+            );
+        }
+        ("PUT", "pause") => player.is_playing = false,
+        ("POST", "next") => player.skipped += 1,
+        ("POST", "previous") => player.skipped -= 1,
+        ("PUT", "seek") => player.position_ms = number(request, "position_ms", 0) as u32,
+        ("PUT", "volume") => match number(request, "volume_percent", 101) {
+            volume_percent @ 0..=100 => player.volume_percent = volume_percent as u32,
+            _ => return Response::error(400, "Invalid volume_percent", None),
+        },
+        ("PUT", "shuffle") => {
+            player.shuffle = request.query.get("state").map(String::as_str) == Some("true")
+        }
+        ("PUT", "repeat") => match request.query.get("state").map(String::as_str) {
+            Some(repeat @ ("track" | "context" | "off")) => player.repeat = repeat.to_string(),
+            _ => return Response::error(400, "Invalid state", None),
+        },
+        _ => return Response::error(404, "Service not found", None),
+    }
+    player.device_id = device_id;
+
+    Response::no_content()
+}
+
+fn token(request: &Request) -> Response {
+    let params = parse_form(&String::from_utf8_lossy(&request.body));
+    if params.get("grant_type").map(String::as_str) != Some("refresh_token")
+        || params.get("refresh_token").map(String::as_str) != Some(REFRESH_TOKEN)
+    {
+        return Response {
+            status: 400,
+            body: Some(json!({
+                "error": "invalid_grant",
+                "error_description": "Invalid refresh token"
+            })),
+            retry_after: None,
+        };
+    }
+
+    Response::ok(json!({
+        "access_token": ACCESS_TOKEN,
+        "token_type": "Bearer",
+        "scope": "user-follow-read user-follow-modify",
+        "expires_in": 3600,
+    }))
+}
+
+fn search(state: &SpotifyState, request: &Request) -> Response {
+    let genre = request
+        .query
+        .get("q")
+        .and_then(|q| q.strip_prefix("genre:"))
+        .unwrap_or_default();
+    let offset = number(request, "offset", 0);
+    let limit = number(request, "limit", 20);
+    if offset > 1000 {
+        return Response::error(400, "Invalid offset", None);
+    }
+    let matched = state
+        .artists
+        .iter()
+        .filter(|artist| artist.genres.iter().any(|g| g == genre))
+        .collect::<Vec<_>>();
+    let items = matched
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(|artist| artist.to_json())
+        .collect::<Vec<_>>();
+    let next = (offset + items.len() < matched.len()).then(|| {
+        format!(
+            "https://api.spotify.com/v1/search?offset={}&limit={}",
+            offset + items.len(),
+            limit
+        )
+    });
+
+    Response::ok(json!({
+        "artists": {
+            "href": "https://api.spotify.com/v1/search",
+            "limit": limit,
+            "next": next,
+            "offset": offset,
+            "previous": null,
+            "total": matched.len(),
+            "items": items,
+        }
+    }))
+}
+
+fn followed_artists(state: &SpotifyState, request: &Request) -> Response {
+    let limit = number(request, "limit", 20);
+    let after = request.query.get("after");
+    let followed = state
+        .following
+        .iter()
+        .filter(|id| after.is_none_or(|after| *id > after))
+        .filter_map(|id| state.find_artist(id))
+        .collect::<Vec<_>>();
+    let items = followed.iter().take(limit).collect::<Vec<_>>();
+    let after = (followed.len() > items.len())
+        .then(|| items.last().map(|artist| artist.id.clone()))
+        .flatten();
+
+    Response::ok(json!({
+        "artists": {
+            "href": "https://api.spotify.com/v1/me/following",
+            "limit": limit,
+            "next": after.as_ref().map(|_| "https://api.spotify.com/v1/me/following"),
+            "cursors": { "after": after, "before": null },
+            "total": state.following.len(),
+            "items": items.iter().map(|artist| artist.to_json()).collect::<Vec<_>>(),
+        }
+    }))
+}
+
+fn too_many_ids() -> Response {
+    Response::error(400, "Too many ids requested", None)
+}
+
+fn ids(request: &Request) -> Vec<String> {
+    request
+        .query
+        .get("ids")
+        .map(|ids| ids.split(',').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn number(request: &Request, key: &str, default: usize) -> usize {
+    request
+        .query
+        .get(key)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn parse_form(value: &str) -> HashMap<String, String> {
+    value
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Error",
+    }
+}
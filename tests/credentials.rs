@@ -0,0 +1,27 @@
+use spotify_mcp::infrastructure::credentials::Credentials;
+use std::fs;
+
+#[test]
+fn save_writes_owner_only_file() {
+    let dir = std::env::temp_dir().join(format!("spotify-mcp-credentials-{}", std::process::id()));
+    let path = dir.join("credentials.json");
+    let credentials = Credentials::new("refresh-token".to_string(), None, true);
+
+    credentials.save(&path).unwrap();
+    credentials.save(&path).unwrap();
+    let loaded = Credentials::load(&path).unwrap().unwrap();
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777
+    };
+    let entries = fs::read_dir(&dir).unwrap().count();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(loaded.refresh_token, "refresh-token");
+    assert!(loaded.pkce);
+    #[cfg(unix)]
+    assert_eq!(mode, 0o600);
+    // 一時ファイルは残らない
+    assert_eq!(entries, 1);
+}
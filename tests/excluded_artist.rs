@@ -0,0 +1,100 @@
+use spotify_mcp::{
+    model::{
+        excluded_artist::{ExcludedArtist, InsertInput},
+        exclusion_category::ExclusionCategory,
+    },
+    repository::{ExcludedArtistRepository, mysql::MySqlRepository},
+};
+use sqlx::MySqlPool;
+
+#[sqlx::test]
+async fn insert_returns_inserted_row(db_pool: MySqlPool) {
+    let input = InsertInput::new(
+        "0TnOYISbd1XYRBk9myaseg".to_string(),
+        "Pitbull".to_string(),
+        Some("よく聴いている".to_string()),
+        Some(ExclusionCategory::AlreadyKnown),
+        None,
+        None,
+    );
+
+    let excluded_artist = ExcludedArtist::insert(&db_pool, &input).await.unwrap();
+
+    assert_eq!(excluded_artist.id, "0TnOYISbd1XYRBk9myaseg");
+    assert_eq!(excluded_artist.name, "Pitbull");
+    assert_eq!(excluded_artist.reason.as_deref(), Some("よく聴いている"));
+    assert_eq!(
+        excluded_artist.category,
+        Some(ExclusionCategory::AlreadyKnown)
+    );
+}
+
+#[sqlx::test]
+async fn insert_returns_each_inserted_row(db_pool: MySqlPool) {
+    for (id, name) in [
+        ("0TnOYISbd1XYRBk9myaseg", "Pitbull"),
+        ("1dfeR4HaWDbWqFHLkxsg1d", "Queen"),
+    ] {
+        let input = InsertInput::new(id.to_string(), name.to_string(), None, None, None, None);
+
+        let excluded_artist = ExcludedArtist::insert(&db_pool, &input).await.unwrap();
+
+        assert_eq!(excluded_artist.id, id);
+        assert_eq!(excluded_artist.name, name);
+    }
+}
+
+#[sqlx::test]
+async fn find_by_ids_returns_all_inserted_rows(db_pool: MySqlPool) {
+    let artists = [
+        ("0TnOYISbd1XYRBk9myaseg", "Pitbull"),
+        ("1dfeR4HaWDbWqFHLkxsg1d", "Queen"),
+        ("3WrFJ7ztbogyGnTHbHJFl2", "The Beatles"),
+    ];
+    for (id, name) in artists {
+        let input = InsertInput::new(id.to_string(), name.to_string(), None, None, None, None);
+        ExcludedArtist::insert(&db_pool, &input).await.unwrap();
+    }
+    let ids = artists.map(|(id, _)| id.to_string());
+
+    let excluded_artists = ExcludedArtist::find_by_ids(&db_pool, &ids).await.unwrap();
+
+    let found = excluded_artists
+        .iter()
+        .map(|excluded_artist| excluded_artist.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            "3WrFJ7ztbogyGnTHbHJFl2",
+            "1dfeR4HaWDbWqFHLkxsg1d",
+            "0TnOYISbd1XYRBk9myaseg",
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn filter_not_excluded_returns_only_not_excluded_ids(db_pool: MySqlPool) {
+    let repository = MySqlRepository::new(db_pool);
+    for (id, name) in [
+        ("0TnOYISbd1XYRBk9myaseg", "Pitbull"),
+        ("3WrFJ7ztbogyGnTHbHJFl2", "The Beatles"),
+    ] {
+        let input = InsertInput::new(id.to_string(), name.to_string(), None, None, None, None);
+        repository.insert(&input).await.unwrap();
+    }
+    let ids = [
+        "0TnOYISbd1XYRBk9myaseg",
+        "1dfeR4HaWDbWqFHLkxsg1d",
+        "3WrFJ7ztbogyGnTHbHJFl2",
+        "6olE6TJLqED3rqDCT0FyPh",
+    ]
+    .map(String::from);
+
+    let not_excluded = repository.filter_not_excluded(&ids).await.unwrap();
+
+    assert_eq!(
+        not_excluded,
+        ["1dfeR4HaWDbWqFHLkxsg1d", "6olE6TJLqED3rqDCT0FyPh"]
+    );
+}
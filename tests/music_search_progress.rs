@@ -0,0 +1,59 @@
+use spotify_mcp::model::{
+    music_genre::{self, MusicGenre},
+    music_search_progress::{MusicSearchProgress, UpsertInput},
+};
+use sqlx::MySqlPool;
+
+async fn create_genre(db_pool: &MySqlPool, search_key: &str) -> MusicGenre {
+    let input = music_genre::InsertInput::new(search_key.to_string(), search_key.to_string());
+
+    MusicGenre::insert(db_pool, &input).await.unwrap()
+}
+
+#[sqlx::test]
+async fn upsert_inserts_new_progress(db_pool: MySqlPool) {
+    let genre = create_genre(&db_pool, "j-pop").await;
+
+    let progress = MusicSearchProgress::upsert(&db_pool, genre.id, &UpsertInput::new(0))
+        .await
+        .unwrap();
+
+    assert_eq!(progress.music_genre_id, genre.id);
+    assert_eq!(progress.position, 0);
+}
+
+#[sqlx::test]
+async fn upsert_returns_updated_row_on_duplicate(db_pool: MySqlPool) {
+    let genre = create_genre(&db_pool, "j-pop").await;
+    let other_genre = create_genre(&db_pool, "k-pop").await;
+    let inserted = MusicSearchProgress::upsert(&db_pool, genre.id, &UpsertInput::new(0))
+        .await
+        .unwrap();
+    // 直前に別の行を登録して last_insert_id が別の進捗を指す状態にする
+    MusicSearchProgress::upsert(&db_pool, other_genre.id, &UpsertInput::new(0))
+        .await
+        .unwrap();
+
+    let updated = MusicSearchProgress::upsert(&db_pool, genre.id, &UpsertInput::new(30))
+        .await
+        .unwrap();
+
+    assert_eq!(updated.id, inserted.id);
+    assert_eq!(updated.music_genre_id, genre.id);
+    assert_eq!(updated.position, 30);
+}
+
+#[sqlx::test]
+async fn upsert_returns_row_when_position_is_unchanged(db_pool: MySqlPool) {
+    let genre = create_genre(&db_pool, "j-pop").await;
+    let inserted = MusicSearchProgress::upsert(&db_pool, genre.id, &UpsertInput::new(10))
+        .await
+        .unwrap();
+
+    let updated = MusicSearchProgress::upsert(&db_pool, genre.id, &UpsertInput::new(10))
+        .await
+        .unwrap();
+
+    assert_eq!(updated.id, inserted.id);
+    assert_eq!(updated.position, 10);
+}
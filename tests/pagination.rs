@@ -0,0 +1,90 @@
+mod common;
+
+use common::{rock_artists, setup};
+use futures::TryStreamExt;
+use spotify_mcp::client::spotify::v1::search::artist::Artist;
+
+fn ids(artists: &[Artist]) -> Vec<String> {
+    artists.iter().map(|artist| artist.id.clone()).collect()
+}
+
+#[tokio::test]
+async fn followed_artists_follow_cursor_across_pages() {
+    let artists = rock_artists(120);
+    let expected = artists
+        .iter()
+        .map(|artist| artist.id.clone())
+        .collect::<Vec<_>>();
+    let context = setup(artists).await;
+    context.spotify.state().following.extend(expected.clone());
+
+    let followed = context
+        .server
+        .spotify()
+        .followed_artists_stream(None)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&followed), expected);
+    assert_eq!(context.spotify.request_count("GET /v1/me/following"), 3);
+}
+
+#[tokio::test]
+async fn followed_artists_stop_at_max_items() {
+    let artists = rock_artists(120);
+    let context = setup(artists.clone()).await;
+    context
+        .spotify
+        .state()
+        .following
+        .extend(artists.into_iter().map(|artist| artist.id));
+
+    let followed = context
+        .server
+        .spotify()
+        .followed_artists_stream(Some(70))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(followed.len(), 70);
+    assert_eq!(followed[69].id, "artist0069");
+    assert_eq!(context.spotify.request_count("GET /v1/me/following"), 2);
+}
+
+#[tokio::test]
+async fn search_follows_offset_across_pages_up_to_max_items() {
+    let context = setup(rock_artists(200)).await;
+
+    let found = context
+        .server
+        .spotify()
+        .search_artists_stream("rock", 10, Some(60))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(found.len(), 60);
+    assert_eq!(found[0].id, "artist0010");
+    assert_eq!(found[59].id, "artist0069");
+    assert_eq!(context.spotify.request_count("GET /v1/search"), 2);
+}
+
+#[tokio::test]
+async fn search_stops_at_offset_limit() {
+    let context = setup(rock_artists(1100)).await;
+
+    // offset の上限 (1000) を超えるとエラーになるため、その手前で打ち切る
+    let found = context
+        .server
+        .spotify()
+        .search_artists_stream("rock", 900, None)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(found.len(), 100);
+    assert_eq!(found[99].id, "artist0999");
+    assert_eq!(context.spotify.request_count("GET /v1/search"), 2);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{MockSpotify, artist, artist_search, test_config, text};
+use spotify_mcp::{
+    config::{CassetteConfig, CassetteMode, Config},
+    server::{FollowQuery, IsFollowingQuery, SearchQuery},
+};
+use std::{fs, path::PathBuf};
+
+// 再生時は API に接続しないため、使われていないポートを指定する
+const UNREACHABLE_BASE_URL: &str = "http://127.0.0.1:1";
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn with_cassette(mut config: Config, mode: CassetteMode, path: PathBuf) -> Config {
+    config.spotify.cassette = Some(CassetteConfig { mode, path });
+    config
+}
+
+fn search_query(genre: &str) -> SearchQuery {
+    SearchQuery {
+        genre: genre.to_string(),
+        position: 0,
+    }
+}
+
+#[tokio::test]
+async fn replays_recorded_fixture_without_network() {
+    let config = with_cassette(
+        test_config(UNREACHABLE_BASE_URL),
+        CassetteMode::Replay,
+        fixture_path("search_rock.json"),
+    );
+    let server = artist_search(&config);
+
+    let result = server.search(search_query("rock")).await.unwrap();
+
+    assert!(text(&result).contains("アーティストID: a1\nアーティスト名: Rock Band"));
+}
+
+#[tokio::test]
+async fn replay_fails_on_unrecorded_request() {
+    let config = with_cassette(
+        test_config(UNREACHABLE_BASE_URL),
+        CassetteMode::Replay,
+        fixture_path("search_rock.json"),
+    );
+    let server = artist_search(&config);
+
+    let error = server.search(search_query("jazz")).await.unwrap_err();
+
+    assert!(
+        error
+            .message
+            .contains("記録されていないリクエストです: GET /v1/search?type=artist&q=genre%3Ajazz")
+    );
+}
+
+#[tokio::test]
+async fn records_scrubbed_interactions_and_replays_them() {
+    let path = std::env::temp_dir().join(format!(
+        "spotify-mcp-cassette-{}-record.json",
+        std::process::id()
+    ));
+    let spotify = MockSpotify::start(vec![artist("a1", "Rock Band", &["rock"])]).await;
+    let recorder = artist_search(&with_cassette(
+        spotify.config(),
+        CassetteMode::Record,
+        path.clone(),
+    ));
+    recorder
+        .follow(FollowQuery {
+            ids: vec!["a1".to_string()],
+        })
+        .await
+        .unwrap();
+    let recorded = recorder
+        .is_following(IsFollowingQuery {
+            ids: vec!["a1".to_string()],
+        })
+        .await
+        .unwrap();
+
+    let fixture = fs::read_to_string(&path).unwrap();
+    assert!(!fixture.contains(common::ACCESS_TOKEN));
+    assert!(!fixture.contains(common::REFRESH_TOKEN));
+    assert!(!fixture.contains("test-client-secret"));
+
+    let player = artist_search(&with_cassette(
+        test_config(UNREACHABLE_BASE_URL),
+        CassetteMode::Replay,
+        path.clone(),
+    ));
+    player
+        .follow(FollowQuery {
+            ids: vec!["a1".to_string()],
+        })
+        .await
+        .unwrap();
+    let replayed = player
+        .is_following(IsFollowingQuery {
+            ids: vec!["a1".to_string()],
+        })
+        .await
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(text(&replayed), text(&recorded));
+    assert!(text(&replayed).contains("フォロー状況: フォロー済み"));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{MockSpotify, artist, artist_search, text};
+use spotify_mcp::server::{ArtistSearch, DeviceQuery, SearchQuery};
+
+fn server_with_retries(spotify: &MockSpotify, max_retries: u32) -> ArtistSearch {
+    let mut config = spotify.config();
+    config.spotify.retry.max_retries = max_retries;
+
+    artist_search(&config)
+}
+
+fn rock_query() -> SearchQuery {
+    SearchQuery {
+        genre: "rock".to_string(),
+        position: 0,
+    }
+}
+
+#[tokio::test]
+async fn retries_rate_limit_server_error_and_expired_token() {
+    let spotify = MockSpotify::start(vec![artist("a1", "Rock Band", &["rock"])]).await;
+    let server = server_with_retries(&spotify, 2);
+    spotify.state().failures.extend([429, 503, 401]);
+
+    let result = server.search(rock_query()).await.unwrap();
+
+    assert!(text(&result).contains("アーティスト名: Rock Band"));
+    // 401 ではトークンを取得し直し、再試行の回数には数えない
+    assert_eq!(
+        spotify.state().requests,
+        vec![
+            "POST /api/token",
+            "GET /v1/search",
+            "GET /v1/search",
+            "GET /v1/search",
+            "POST /api/token",
+            "GET /v1/search",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let spotify = MockSpotify::start(vec![artist("a1", "Rock Band", &["rock"])]).await;
+    let server = server_with_retries(&spotify, 2);
+    spotify.state().failures.extend([503, 503, 503]);
+
+    let error = server.search(rock_query()).await.unwrap_err();
+
+    assert!(error.message.contains("503"));
+    assert_eq!(spotify.state().requests.len(), 4);
+}
+
+#[tokio::test]
+async fn does_not_retry_non_idempotent_request_on_server_error() {
+    let spotify = MockSpotify::start(vec![]).await;
+    let server = server_with_retries(&spotify, 2);
+    spotify.state().failures.push_back(503);
+
+    let error = server
+        .skip_to_next(DeviceQuery { device_id: None })
+        .await
+        .unwrap_err();
+
+    assert!(error.message.contains("503"));
+    assert_eq!(spotify.state().player.skipped, 0);
+    assert_eq!(
+        spotify.state().requests,
+        vec!["POST /api/token", "POST /v1/me/player/next"]
+    );
+}
+
+#[tokio::test]
+async fn refreshes_token_only_once() {
+    let spotify = MockSpotify::start(vec![]).await;
+    let server = server_with_retries(&spotify, 2);
+    spotify.state().failures.extend([401, 401]);
+
+    let error = server.search(rock_query()).await.unwrap_err();
+
+    assert!(error.message.contains("認証に失敗しました"));
+    assert_eq!(
+        spotify.state().requests,
+        vec![
+            "POST /api/token",
+            "GET /v1/search",
+            "POST /api/token",
+            "GET /v1/search",
+        ]
+    );
+}
@@ -0,0 +1,410 @@
+use chrono::{Duration, Utc};
+use spotify_mcp::{
+    config::{DatabaseBackend, DatabaseConfig},
+    infrastructure::database::DatabasePool,
+    model::{
+        excluded_artist::{self, ExcludedArtist},
+        exclusion_category::ExclusionCategory,
+        music_genre::{self, MusicGenre, error::MusicGenreError},
+        sort_order::SortOrder,
+    },
+    repository::Repositories,
+};
+
+// インメモリのデータベースは接続ごとに別になるため、接続は1つだけにする
+async fn repositories() -> Repositories {
+    let pool = DatabasePool::connect(&DatabaseConfig {
+        backend: DatabaseBackend::Sqlite,
+        url: "sqlite::memory:".to_string(),
+        max_connections: 1,
+    })
+    .await
+    .unwrap();
+    pool.migrate().await.unwrap();
+
+    pool.into_repositories()
+}
+
+async fn create_genre(repositories: &Repositories, search_key: &str) -> MusicGenre {
+    let input = music_genre::InsertInput::new(search_key.to_string(), search_key.to_string());
+
+    repositories.music_genres.insert(&input).await.unwrap()
+}
+
+fn excluded_artist_input(id: &str, expires_in_days: Option<i64>) -> excluded_artist::InsertInput {
+    excluded_artist::InsertInput::new(
+        id.to_string(),
+        format!("Artist {id}"),
+        None,
+        None,
+        expires_in_days.map(|days| (Utc::now() + Duration::days(days)).naive_utc()),
+        None,
+    )
+}
+
+fn ids(excluded_artists: &[ExcludedArtist]) -> Vec<&str> {
+    excluded_artists
+        .iter()
+        .map(|excluded_artist| excluded_artist.id.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn find_or_create_creates_once() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+
+    let created = repositories
+        .music_search_progresses
+        .find_or_create(genre.id)
+        .await
+        .unwrap();
+    let found = repositories
+        .music_search_progresses
+        .find_or_create(genre.id)
+        .await
+        .unwrap();
+
+    assert_eq!(created.position, 0);
+    assert_eq!(found.id, created.id);
+}
+
+#[tokio::test]
+async fn advance_stops_at_total_and_completes() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    let progresses = &repositories.music_search_progresses;
+    progresses.find_or_create(genre.id).await.unwrap();
+    progresses.record_total(genre.id, 25).await.unwrap();
+
+    let advanced = progresses
+        .advance(genre.id, None, 10)
+        .await
+        .unwrap()
+        .unwrap();
+    let stale = progresses.advance(genre.id, Some(0), 10).await.unwrap();
+    let capped = progresses
+        .advance(genre.id, Some(10), 100)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(advanced.position, 10);
+    assert!(!advanced.is_completed());
+    assert!(stale.is_none());
+    assert_eq!(capped.position, 25);
+    assert!(capped.is_completed());
+}
+
+#[tokio::test]
+async fn advance_stops_at_search_offset_limit() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    let progresses = &repositories.music_search_progresses;
+    progresses.find_or_create(genre.id).await.unwrap();
+
+    let advanced = progresses
+        .advance(genre.id, None, u32::MAX)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(advanced.position, 1000);
+    assert!(advanced.is_completed());
+}
+
+#[tokio::test]
+async fn record_total_completes_when_position_reached() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    let progresses = &repositories.music_search_progresses;
+    progresses.find_or_create(genre.id).await.unwrap();
+    progresses.advance(genre.id, None, 30).await.unwrap();
+
+    progresses.record_total(genre.id, 20).await.unwrap();
+    let progress = progresses
+        .find_by_music_genre_id(genre.id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(progress.last_total, Some(20));
+    assert!(progress.is_completed());
+}
+
+#[tokio::test]
+async fn complete_and_reset() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    let progresses = &repositories.music_search_progresses;
+    progresses.find_or_create(genre.id).await.unwrap();
+    progresses.advance(genre.id, None, 10).await.unwrap();
+    progresses.record_total(genre.id, 100).await.unwrap();
+
+    progresses.complete(genre.id).await.unwrap();
+    let completed = progresses
+        .find_by_music_genre_id(genre.id)
+        .await
+        .unwrap()
+        .unwrap();
+    let reset = progresses.reset(genre.id, 0).await.unwrap();
+
+    assert!(completed.is_completed());
+    assert_eq!(completed.position, 10);
+    assert_eq!(reset.id, completed.id);
+    assert_eq!(reset.position, 0);
+    assert!(!reset.is_completed());
+    assert_eq!(reset.last_total, Some(100));
+}
+
+#[tokio::test]
+async fn excluded_artist_insert_returns_row() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    let input = excluded_artist::InsertInput::new(
+        "a1".to_string(),
+        "Rock Band".to_string(),
+        Some("よく聴いている".to_string()),
+        Some(ExclusionCategory::AlreadyKnown),
+        None,
+        Some(genre.id),
+    );
+
+    let excluded_artist = repositories.excluded_artists.insert(&input).await.unwrap();
+
+    assert_eq!(excluded_artist.id, "a1");
+    assert_eq!(excluded_artist.reason.as_deref(), Some("よく聴いている"));
+    assert_eq!(
+        excluded_artist.category,
+        Some(ExclusionCategory::AlreadyKnown)
+    );
+    assert_eq!(excluded_artist.source_music_genre_id, Some(genre.id));
+}
+
+#[tokio::test]
+async fn excluded_artist_bulk_insert_counts_new_rows() {
+    let repositories = repositories().await;
+    let excluded_artists = &repositories.excluded_artists;
+    excluded_artists
+        .insert(&excluded_artist_input("a1", None))
+        .await
+        .unwrap();
+
+    let inserted = excluded_artists
+        .bulk_insert(&[
+            excluded_artist_input("a1", None),
+            excluded_artist_input("a2", None),
+            excluded_artist_input("a3", None),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(inserted, 2);
+    assert_eq!(excluded_artists.count().await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn excluded_artist_bulk_insert_fails_on_unknown_genre() {
+    let repositories = repositories().await;
+    let excluded_artists = &repositories.excluded_artists;
+
+    let result = excluded_artists
+        .bulk_insert(&[
+            excluded_artist_input("a1", None),
+            excluded_artist::InsertInput::new(
+                "a2".to_string(),
+                "Artist a2".to_string(),
+                None,
+                None,
+                None,
+                Some(1),
+            ),
+        ])
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(excluded_artists.count().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn expired_exclusions_are_not_excluded() {
+    let repositories = repositories().await;
+    let excluded_artists = &repositories.excluded_artists;
+    excluded_artists
+        .bulk_insert(&[
+            excluded_artist_input("a1", None),
+            excluded_artist_input("a2", Some(-1)),
+            excluded_artist_input("a3", Some(1)),
+        ])
+        .await
+        .unwrap();
+    let all_ids = ["a1", "a2", "a3", "a4"].map(String::from);
+
+    let found = excluded_artists.find_by_ids(&all_ids).await.unwrap();
+    let not_excluded = excluded_artists
+        .filter_not_excluded(&all_ids)
+        .await
+        .unwrap();
+    let searched = excluded_artists.search_by_name("Artist", 10).await.unwrap();
+    let listed = excluded_artists
+        .find_all(&excluded_artist::ListInput::new(10, 0, SortOrder::Asc))
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&found), ["a3", "a1"]);
+    assert_eq!(not_excluded, ["a2", "a4"]);
+    assert_eq!(ids(&searched), ["a1", "a3"]);
+    assert_eq!(ids(&listed), ["a1", "a3"]);
+    assert_eq!(excluded_artists.count().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn expired_exclusions_can_be_excluded_again() {
+    let repositories = repositories().await;
+    let excluded_artists = &repositories.excluded_artists;
+    excluded_artists
+        .bulk_insert(&[
+            excluded_artist_input("a1", Some(-1)),
+            excluded_artist_input("a2", Some(-1)),
+            excluded_artist_input("a3", Some(1)),
+        ])
+        .await
+        .unwrap();
+
+    let reinserted = excluded_artists
+        .insert(&excluded_artist_input("a1", None))
+        .await
+        .unwrap();
+    let inserted = excluded_artists
+        .bulk_insert(&[
+            excluded_artist_input("a2", Some(1)),
+            excluded_artist_input("a3", None),
+        ])
+        .await
+        .unwrap();
+    let duplicated = excluded_artists
+        .insert(&excluded_artist_input("a3", None))
+        .await;
+
+    assert_eq!(reinserted.expires_at, None);
+    assert_eq!(inserted, 1);
+    assert!(duplicated.is_err());
+    let all_ids = ["a1", "a2", "a3"].map(String::from);
+    assert!(
+        excluded_artists
+            .filter_not_excluded(&all_ids)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn genre_bulk_insert_counts_new_rows() {
+    let repositories = repositories().await;
+    create_genre(&repositories, "rock").await;
+    let inputs = ["rock", "jazz", "j-pop", "jazz"].map(|search_key| {
+        music_genre::InsertInput::new(search_key.to_string(), search_key.to_string())
+    });
+
+    let inserted = repositories
+        .music_genres
+        .bulk_insert(&inputs)
+        .await
+        .unwrap();
+
+    assert_eq!(inserted, 2);
+    assert_eq!(repositories.music_genres.find_all().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn delete_genre_without_cascade_keeps_referenced_genre() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    repositories
+        .music_search_progresses
+        .find_or_create(genre.id)
+        .await
+        .unwrap();
+
+    let error = repositories
+        .music_genres
+        .delete(genre.id, false)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<MusicGenreError>(),
+        Some(MusicGenreError::Referenced(id)) if *id == genre.id
+    ));
+    assert!(
+        repositories
+            .music_genres
+            .find_by_id(genre.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn delete_genre_with_cascade_removes_progress() {
+    let repositories = repositories().await;
+    let genre = create_genre(&repositories, "rock").await;
+    repositories
+        .music_search_progresses
+        .find_or_create(genre.id)
+        .await
+        .unwrap();
+    let input = excluded_artist::InsertInput::new(
+        "a1".to_string(),
+        "Rock Band".to_string(),
+        None,
+        None,
+        None,
+        Some(genre.id),
+    );
+    repositories.excluded_artists.insert(&input).await.unwrap();
+
+    repositories
+        .music_genres
+        .delete(genre.id, true)
+        .await
+        .unwrap();
+
+    assert!(
+        repositories
+            .music_genres
+            .find_by_id(genre.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repositories
+            .music_search_progresses
+            .find_by_music_genre_id(genre.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    // 除外の記録は残し、きっかけになったジャンルだけ外す
+    let excluded_artists = repositories
+        .excluded_artists
+        .find_by_ids(&["a1".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(excluded_artists[0].source_music_genre_id, None);
+}
+
+#[tokio::test]
+async fn delete_unknown_genre_is_not_found() {
+    let repositories = repositories().await;
+
+    let error = repositories.music_genres.delete(1, true).await.unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<MusicGenreError>(),
+        Some(MusicGenreError::NotFound(1))
+    ));
+}
@@ -0,0 +1,1003 @@
+mod common;
+
+use common::{MockDevice, artist, device, json, rock_artists, setup, text};
+use rmcp::model::ErrorCode;
+use spotify_mcp::{
+    client::spotify::v1::me::player::repeat::RepeatState,
+    model::exclusion_category::ExclusionCategory, server::*,
+};
+
+#[tokio::test]
+async fn search_returns_artists_of_genre() {
+    let context = setup(vec![
+        artist("a1", "Rock Band", &["rock", "alternative rock"]),
+        artist("a2", "Pop Singer", &["pop"]),
+    ])
+    .await;
+
+    let result = context
+        .server
+        .search(SearchQuery {
+            genre: "rock".to_string(),
+            position: 0,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("アーティスト名: Rock Band"));
+    assert!(!text(&result).contains("Pop Singer"));
+    // アクセストークンは最初の呼び出しで一度だけ取得する
+    let requests = context.spotify.state().requests.clone();
+    assert_eq!(requests, vec!["POST /api/token", "GET /v1/search"]);
+}
+
+#[tokio::test]
+async fn search_reports_no_results() {
+    let context = setup(vec![]).await;
+
+    let result = context
+        .server
+        .search(SearchQuery {
+            genre: "jazz".to_string(),
+            position: 0,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("見つかりませんでした"));
+}
+
+#[tokio::test]
+async fn follow_and_is_following() {
+    let context = setup(vec![artist("a1", "Rock Band", &["rock"])]).await;
+
+    context
+        .server
+        .follow(FollowQuery {
+            ids: vec!["a1".to_string()],
+        })
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .is_following(IsFollowingQuery {
+            ids: vec!["a1".to_string(), "unknown".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        json(&result),
+        serde_json::json!([
+            { "id": "a1", "name": "Rock Band", "following": true },
+            { "id": "unknown", "name": null, "following": false },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn follow_and_is_following_split_ids_into_batches() {
+    let artists = rock_artists(120);
+    let ids = artists
+        .iter()
+        .rev()
+        .map(|artist| artist.id.clone())
+        .collect::<Vec<_>>();
+    let context = setup(artists).await;
+
+    context
+        .server
+        .follow(FollowQuery {
+            ids: ids.iter().step_by(2).cloned().collect(),
+        })
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .is_following(IsFollowingQuery { ids: ids.clone() })
+        .await
+        .unwrap();
+
+    let statuses = json(&result);
+    let statuses = statuses.as_array().unwrap();
+    assert_eq!(statuses.len(), 120);
+    for (i, (status, id)) in statuses.iter().zip(&ids).enumerate() {
+        assert_eq!(status["id"], *id);
+        assert_eq!(status["name"], format!("Artist {}", 119 - i));
+        assert_eq!(status["following"], i % 2 == 0);
+    }
+    // 60件のフォローは2回、120件の確認と取得はそれぞれ3回に分けて送る
+    let spotify = &context.spotify;
+    assert_eq!(spotify.request_count("PUT /v1/me/following"), 2);
+    assert_eq!(spotify.request_count("GET /v1/me/following/contains"), 3);
+    assert_eq!(spotify.request_count("GET /v1/artists"), 3);
+}
+
+fn play_query(context_uri: &str) -> PlayQuery {
+    PlayQuery {
+        context_uri: Some(context_uri.to_string()),
+        uris: None,
+        offset_position: None,
+        offset_uri: None,
+        position_ms: None,
+        device_id: None,
+        auto_select_device: None,
+    }
+}
+
+fn device_query() -> DeviceQuery {
+    DeviceQuery { device_id: None }
+}
+
+#[tokio::test]
+async fn play_sends_context_uri() {
+    let context = setup(vec![]).await;
+
+    context
+        .server
+        .play(play_query("spotify:artist:a1"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        context.spotify.state().player.played,
+        Some(serde_json::json!({ "context_uri": "spotify:artist:a1" }))
+    );
+}
+
+#[tokio::test]
+async fn play_sends_uris_with_offset_and_device() {
+    let context = setup(vec![]).await;
+
+    context
+        .server
+        .play(PlayQuery {
+            context_uri: None,
+            uris: Some(vec![
+                "spotify:track:t1".to_string(),
+                "spotify:track:t2".to_string(),
+            ]),
+            offset_position: None,
+            offset_uri: Some("spotify:track:t2".to_string()),
+            position_ms: Some(30000),
+            device_id: Some("d1".to_string()),
+            auto_select_device: None,
+        })
+        .await
+        .unwrap();
+
+    let state = context.spotify.state();
+    assert_eq!(
+        state.player.played,
+        Some(serde_json::json!({
+            "uris": ["spotify:track:t1", "spotify:track:t2"],
+            "offset": { "uri": "spotify:track:t2" },
+            "position_ms": 30000,
+        }))
+    );
+    assert_eq!(state.player.device_id.as_deref(), Some("d1"));
+}
+
+#[tokio::test]
+async fn play_requires_either_context_uri_or_uris() {
+    let context = setup(vec![]).await;
+    let mut query = play_query("spotify:artist:a1");
+    query.uris = Some(vec!["spotify:track:t1".to_string()]);
+
+    let error = context.server.play(query).await.unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert!(context.spotify.state().player.played.is_none());
+}
+
+#[tokio::test]
+async fn play_without_active_device_is_not_found() {
+    let context = setup(vec![]).await;
+    context.spotify.state().devices[0].is_active = false;
+
+    let error = context
+        .server
+        .play(play_query("spotify:artist:a1"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+    assert!(error.message.contains("アクティブなデバイスがありません"));
+}
+
+#[tokio::test]
+async fn pause_and_resume() {
+    let context = setup(vec![]).await;
+    context
+        .server
+        .play(play_query("spotify:artist:a1"))
+        .await
+        .unwrap();
+
+    context.server.pause(device_query()).await.unwrap();
+    let error = context.server.pause(device_query()).await.unwrap_err();
+    assert!(!context.spotify.state().player.is_playing);
+    context.server.resume(device_query()).await.unwrap();
+
+    assert!(context.spotify.state().player.is_playing);
+    assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+    assert!(error.message.contains("すでに一時停止しています"));
+}
+
+#[tokio::test]
+async fn skip_to_next_and_previous() {
+    let context = setup(vec![]).await;
+
+    context.server.skip_to_next(device_query()).await.unwrap();
+    context.server.skip_to_next(device_query()).await.unwrap();
+    context
+        .server
+        .skip_to_previous(device_query())
+        .await
+        .unwrap();
+
+    assert_eq!(context.spotify.state().player.skipped, 1);
+}
+
+#[tokio::test]
+async fn seek_moves_position() {
+    let context = setup(vec![]).await;
+
+    let result = context
+        .server
+        .seek(SeekQuery {
+            position_ms: 90000,
+            device_id: Some("d1".to_string()),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "再生位置を90000ミリ秒に移動しました");
+    let state = context.spotify.state();
+    assert_eq!(state.player.position_ms, 90000);
+    assert_eq!(state.player.device_id.as_deref(), Some("d1"));
+}
+
+#[tokio::test]
+async fn set_volume_validates_range() {
+    let context = setup(vec![]).await;
+
+    context
+        .server
+        .set_volume(SetVolumeQuery {
+            volume_percent: 40,
+            device_id: None,
+        })
+        .await
+        .unwrap();
+    let error = context
+        .server
+        .set_volume(SetVolumeQuery {
+            volume_percent: 101,
+            device_id: None,
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(context.spotify.state().player.volume_percent, 40);
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn set_shuffle_and_repeat() {
+    let context = setup(vec![]).await;
+
+    context
+        .server
+        .set_shuffle(SetShuffleQuery {
+            state: true,
+            device_id: None,
+        })
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .set_repeat(SetRepeatQuery {
+            state: RepeatState::Track,
+            device_id: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "リピートを1曲リピートに設定しました");
+    let state = context.spotify.state();
+    assert!(state.player.shuffle);
+    assert_eq!(state.player.repeat, "track");
+}
+
+#[tokio::test]
+async fn play_auto_selects_preferred_device() {
+    let context = setup(vec![]).await;
+    context.spotify.state().devices = vec![
+        MockDevice {
+            is_restricted: true,
+            ..device("d1", "Restricted Speaker", "Speaker")
+        },
+        device("d2", "Phone", "Smartphone"),
+        device("d3", "Living Room", "Speaker"),
+    ];
+    let server = common::artist_search(&{
+        let mut config = context.spotify.config();
+        config.spotify.preferred_device = Some("living room".to_string());
+        config
+    });
+    let mut query = play_query("spotify:artist:a1");
+    query.auto_select_device = Some(true);
+
+    let result = server.play(query).await.unwrap();
+
+    assert_eq!(text(&result), "デバイス 'Living Room' で曲を再生しました");
+    assert_eq!(
+        context.spotify.state().player.device_id.as_deref(),
+        Some("d3")
+    );
+}
+
+#[tokio::test]
+async fn play_auto_select_falls_back_to_first_controllable_device() {
+    let context = setup(vec![]).await;
+    context.spotify.state().devices = vec![
+        MockDevice {
+            is_restricted: true,
+            ..device("d1", "Restricted Speaker", "Speaker")
+        },
+        device("d2", "Phone", "Smartphone"),
+    ];
+    let mut query = play_query("spotify:artist:a1");
+    query.auto_select_device = Some(true);
+
+    let result = context.server.play(query).await.unwrap();
+
+    assert_eq!(text(&result), "デバイス 'Phone' で曲を再生しました");
+}
+
+#[tokio::test]
+async fn play_auto_select_keeps_active_device() {
+    let context = setup(vec![]).await;
+    let mut query = play_query("spotify:artist:a1");
+    query.auto_select_device = Some(true);
+
+    let result = context.server.play(query).await.unwrap();
+
+    assert_eq!(text(&result), "曲を再生しました");
+    assert_eq!(context.spotify.state().player.device_id, None);
+}
+
+#[tokio::test]
+async fn play_auto_select_without_devices_is_not_found() {
+    let context = setup(vec![]).await;
+    context.spotify.state().devices.clear();
+    let mut query = play_query("spotify:artist:a1");
+    query.auto_select_device = Some(true);
+
+    let error = context.server.play(query).await.unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+    assert!(context.spotify.state().player.played.is_none());
+}
+
+#[tokio::test]
+async fn get_devices_lists_devices() {
+    let context = setup(vec![]).await;
+    context
+        .spotify
+        .state()
+        .devices
+        .push(device("d2", "Phone", "Smartphone"));
+
+    let result = context.server.get_devices().await.unwrap();
+
+    assert!(text(&result).contains(
+        "デバイスID: d1\nデバイス名: Test Computer\n種類: Computer\n音量: 50%\n状態: アクティブ"
+    ));
+    assert!(text(&result).contains("デバイスID: d2\nデバイス名: Phone"));
+    assert_eq!(json(&result)[1]["is_active"], false);
+}
+
+#[tokio::test]
+async fn transfer_playback_activates_device() {
+    let context = setup(vec![]).await;
+    context
+        .spotify
+        .state()
+        .devices
+        .push(device("d2", "Phone", "Smartphone"));
+
+    context
+        .server
+        .transfer_playback(TransferPlaybackQuery {
+            device_id: "d2".to_string(),
+            play: Some(true),
+        })
+        .await
+        .unwrap();
+    let error = context
+        .server
+        .transfer_playback(TransferPlaybackQuery {
+            device_id: "unknown".to_string(),
+            play: None,
+        })
+        .await
+        .unwrap_err();
+
+    let state = context.spotify.state();
+    assert!(state.devices[1].is_active);
+    assert!(!state.devices[0].is_active);
+    assert!(state.player.is_playing);
+    assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_rate_limiter_status_counts_requests() {
+    let context = setup(vec![]).await;
+    context
+        .server
+        .search(SearchQuery {
+            genre: "rock".to_string(),
+            position: 0,
+        })
+        .await
+        .unwrap();
+
+    let result = context.server.get_rate_limiter_status().await.unwrap();
+
+    assert!(text(&result).contains("累計リクエスト数: 1"));
+}
+
+#[tokio::test]
+async fn insert_and_get_excluded_artist() {
+    let context = setup(vec![]).await;
+
+    context
+        .server
+        .insert_excluded_artist(InsertExcludedArtistQuery {
+            id: "a1".to_string(),
+            name: "Rock Band".to_string(),
+            reason: Some("よく聴いている".to_string()),
+            category: Some(ExclusionCategory::AlreadyKnown),
+            expires_at: None,
+            source_music_genre_id: None,
+        })
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .get_excluded_artists_by_ids(GetExcludedArtistsByIdsQuery {
+            ids: vec!["a1".to_string(), "a2".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("アーティストID: a1"));
+    assert!(text(&result).contains("理由: よく聴いている"));
+    assert!(!text(&result).contains("a2"));
+}
+
+#[tokio::test]
+async fn insert_excluded_artist_rejects_invalid_expiry() {
+    let context = setup(vec![]).await;
+
+    let error = context
+        .server
+        .insert_excluded_artist(InsertExcludedArtistQuery {
+            id: "a1".to_string(),
+            name: "Rock Band".to_string(),
+            reason: None,
+            category: None,
+            expires_at: Some("next week".to_string()),
+            source_music_genre_id: None,
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn expired_exclusion_is_not_excluded() {
+    let context = setup(vec![]).await;
+    context
+        .server
+        .insert_excluded_artist(InsertExcludedArtistQuery {
+            id: "a1".to_string(),
+            name: "Rock Band".to_string(),
+            reason: None,
+            category: None,
+            expires_at: Some("2000-01-01".to_string()),
+            source_music_genre_id: None,
+        })
+        .await
+        .unwrap();
+    insert_excluded_artists(&context, &["a2"]).await;
+
+    let result = context
+        .server
+        .filter_not_excluded_artists(FilterNotExcludedArtistsQuery {
+            ids: vec!["a1".to_string(), "a2".to_string(), "a3".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "除外されていないアーティストID:\na1\na3\n");
+}
+
+#[tokio::test]
+async fn expired_exclusion_can_be_excluded_again() {
+    let context = setup(vec![]).await;
+    let query = |expires_at: Option<&str>| InsertExcludedArtistQuery {
+        id: "a1".to_string(),
+        name: "Rock Band".to_string(),
+        reason: None,
+        category: None,
+        expires_at: expires_at.map(String::from),
+        source_music_genre_id: None,
+    };
+    context
+        .server
+        .insert_excluded_artist(query(Some("2000-01-01")))
+        .await
+        .unwrap();
+
+    context
+        .server
+        .insert_excluded_artist(query(None))
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .filter_not_excluded_artists(FilterNotExcludedArtistsQuery {
+            ids: vec!["a1".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "除外されていないアーティストID:\n");
+}
+
+#[tokio::test]
+async fn insert_excluded_artists_ignores_registered() {
+    let context = setup(vec![]).await;
+    insert_excluded_artists(&context, &["a1"]).await;
+
+    let result = insert_excluded_artists(&context, &["a1", "a2"]).await;
+
+    assert!(text(&result).contains("新規登録: 1件\n登録済み: 1件"));
+}
+
+#[tokio::test]
+async fn insert_excluded_artists_sets_reason_and_category() {
+    let context = setup(vec![]).await;
+
+    context
+        .server
+        .insert_excluded_artists(InsertExcludedArtistsQuery {
+            artists: vec![
+                ExcludedArtistInput {
+                    id: "a1".to_string(),
+                    name: "Rock Band".to_string(),
+                },
+                ExcludedArtistInput {
+                    id: "a2".to_string(),
+                    name: "Jazz Band".to_string(),
+                },
+            ],
+            reason: Some("音が大きすぎる".to_string()),
+            category: Some(ExclusionCategory::Disliked),
+        })
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .get_excluded_artists_by_ids(GetExcludedArtistsByIdsQuery {
+            ids: vec!["a1".to_string(), "a2".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result).matches("理由: 音が大きすぎる").count(), 2);
+    assert_eq!(text(&result).matches("分類: 好みではない").count(), 2);
+}
+
+#[tokio::test]
+async fn delete_excluded_artists_counts_deleted() {
+    let context = setup(vec![]).await;
+    insert_excluded_artists(&context, &["a1", "a2"]).await;
+
+    let result = context
+        .server
+        .delete_excluded_artists(DeleteExcludedArtistsQuery {
+            ids: vec!["a1".to_string(), "a3".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("削除件数: 1件"));
+}
+
+#[tokio::test]
+async fn list_excluded_artists_pages() {
+    let context = setup(vec![]).await;
+    insert_excluded_artists(&context, &["a1", "a2", "a3"]).await;
+
+    let result = context
+        .server
+        .list_excluded_artists(ListExcludedArtistsQuery {
+            limit: Some(2),
+            offset: Some(1),
+            order: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("全3件"));
+    assert_eq!(text(&result).matches("アーティストID:").count(), 2);
+}
+
+#[tokio::test]
+async fn list_excluded_artists_hides_expired() {
+    let context = setup(vec![]).await;
+    insert_excluded_artists(&context, &["a1"]).await;
+    context
+        .server
+        .insert_excluded_artist(InsertExcludedArtistQuery {
+            id: "a2".to_string(),
+            name: "Expired Band".to_string(),
+            reason: None,
+            category: None,
+            expires_at: Some("2000-01-01".to_string()),
+            source_music_genre_id: None,
+        })
+        .await
+        .unwrap();
+
+    let result = context
+        .server
+        .list_excluded_artists(ListExcludedArtistsQuery {
+            limit: None,
+            offset: None,
+            order: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("全1件"));
+    assert!(text(&result).contains("アーティストID: a1"));
+    assert!(!text(&result).contains("a2"));
+}
+
+#[tokio::test]
+async fn search_excluded_artists_by_name() {
+    let context = setup(vec![]).await;
+    insert_excluded_artists(&context, &["a1", "b2"]).await;
+
+    let result = context
+        .server
+        .search_excluded_artists(SearchExcludedArtistsQuery {
+            name: "NAME A".to_string(),
+            limit: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("アーティストID: a1"));
+    assert!(!text(&result).contains("b2"));
+}
+
+#[tokio::test]
+async fn create_rename_and_rekey_music_genre() {
+    let context = setup(vec![]).await;
+    create_music_genre(&context, "ロック", "rock").await;
+
+    context
+        .server
+        .rename_music_genre(RenameMusicGenreQuery {
+            id: 1,
+            name: "ハードロック".to_string(),
+        })
+        .await
+        .unwrap();
+    context
+        .server
+        .update_music_genre_search_key(UpdateMusicGenreSearchKeyQuery {
+            id: 1,
+            search_key: "hard rock".to_string(),
+        })
+        .await
+        .unwrap();
+    let result = context.server.get_music_genres().await.unwrap();
+
+    assert!(text(&result).contains("ID: 1\nジャンル名: ハードロック\n検索キー: hard rock"));
+}
+
+#[tokio::test]
+async fn create_music_genre_rejects_duplicate_and_invalid_search_key() {
+    let context = setup(vec![]).await;
+    create_music_genre(&context, "ロック", "rock").await;
+
+    let duplicate = context
+        .server
+        .create_music_genre(CreateMusicGenreQuery {
+            name: "ロック2".to_string(),
+            search_key: "rock".to_string(),
+        })
+        .await
+        .unwrap_err();
+    let invalid = context
+        .server
+        .create_music_genre(CreateMusicGenreQuery {
+            name: "ロック".to_string(),
+            search_key: "Rock".to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(duplicate.code, ErrorCode::INVALID_REQUEST);
+    assert_eq!(invalid.code, ErrorCode::INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn delete_music_genre_requires_cascade_when_referenced() {
+    let context = setup(vec![]).await;
+    create_music_genre(&context, "ロック", "rock").await;
+    insert_music_search_progress(&context, 1).await;
+
+    let error = context
+        .server
+        .delete_music_genre(DeleteMusicGenreQuery {
+            id: 1,
+            cascade: None,
+        })
+        .await
+        .unwrap_err();
+    context
+        .server
+        .delete_music_genre(DeleteMusicGenreQuery {
+            id: 1,
+            cascade: Some(true),
+        })
+        .await
+        .unwrap();
+    let missing = context
+        .server
+        .delete_music_genre(DeleteMusicGenreQuery {
+            id: 1,
+            cascade: None,
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+    assert_eq!(missing.code, ErrorCode::RESOURCE_NOT_FOUND);
+}
+
+#[tokio::test]
+async fn harvest_music_genres_counts_and_inserts() {
+    let context = setup(vec![
+        artist("a1", "Rock Band", &["rock", "j-rock"]),
+        artist("a2", "Another Band", &["rock"]),
+        artist("a3", "Followed", &["rock", "Invalid Key"]),
+    ])
+    .await;
+    context.spotify.state().following.insert("a3".to_string());
+    create_music_genre(&context, "ロック", "rock").await;
+
+    let result = context
+        .server
+        .harvest_music_genres(HarvestMusicGenresQuery {
+            include_followed: None,
+            search_keys: Some(vec!["rock".to_string()]),
+            search_limit: None,
+            insert: Some(true),
+            min_count: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        json(&result),
+        serde_json::json!([
+            { "genre": "rock", "count": 3, "registered": true },
+            { "genre": "Invalid Key", "count": 1, "registered": false },
+            { "genre": "j-rock", "count": 1, "registered": false },
+        ])
+    );
+    assert!(text(&result).contains("未登録のジャンルを1件登録しました"));
+}
+
+#[tokio::test]
+async fn discover_skips_following_and_excluded_and_advances_progress() {
+    let context = setup(rock_artists(5)).await;
+    context
+        .spotify
+        .state()
+        .following
+        .insert("artist0000".to_string());
+    insert_excluded_artists(&context, &["artist0001"]).await;
+    create_music_genre(&context, "ロック", "rock").await;
+
+    let result = context
+        .server
+        .discover(DiscoverQuery {
+            music_genre_id: 1,
+            count: Some(2),
+        })
+        .await
+        .unwrap();
+
+    let result = json(&result);
+    assert_eq!(result["position"], 4);
+    assert_eq!(result["skipped_following"], 1);
+    assert_eq!(result["skipped_excluded"], 1);
+    assert_eq!(result["candidates"][0]["id"], "artist0002");
+    assert_eq!(result["candidates"][1]["id"], "artist0003");
+    assert_eq!(result["exhausted"], false);
+}
+
+#[tokio::test]
+async fn discover_completes_when_results_run_out() {
+    let context = setup(rock_artists(3)).await;
+    create_music_genre(&context, "ロック", "rock").await;
+
+    context
+        .server
+        .discover(DiscoverQuery {
+            music_genre_id: 1,
+            count: Some(10),
+        })
+        .await
+        .unwrap();
+    let result = context
+        .server
+        .get_music_search_progress(MusicSearchProgressQuery { music_genre_id: 1 })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("現在の検索位置: 3"));
+    assert!(text(&result).contains("進捗: 100.0%"));
+    assert!(text(&result).contains("完了日時:"));
+}
+
+#[tokio::test]
+async fn discover_unknown_music_genre_is_not_found() {
+    let context = setup(vec![]).await;
+
+    let error = context
+        .server
+        .discover(DiscoverQuery {
+            music_genre_id: 1,
+            count: None,
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+}
+
+#[tokio::test]
+async fn update_music_search_progress_rejects_stale_position() {
+    let context = setup(vec![]).await;
+    create_music_genre(&context, "ロック", "rock").await;
+    insert_music_search_progress(&context, 1).await;
+
+    let result = context
+        .server
+        .update_music_search_progress(UpdateMusicSearchProgressQuery {
+            music_genre_id: 1,
+            consumed: Some(5),
+            expected_position: Some(0),
+        })
+        .await
+        .unwrap();
+    let error = context
+        .server
+        .update_music_search_progress(UpdateMusicSearchProgressQuery {
+            music_genre_id: 1,
+            consumed: Some(5),
+            expected_position: Some(0),
+        })
+        .await
+        .unwrap_err();
+
+    assert!(text(&result).contains("現在の検索位置: 5"));
+    assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+    assert!(error.message.contains("現在の検索位置: 5"));
+}
+
+#[tokio::test]
+async fn update_music_search_progress_stops_at_search_offset_limit() {
+    let context = setup(vec![]).await;
+    create_music_genre(&context, "ロック", "rock").await;
+    insert_music_search_progress(&context, 1).await;
+    context
+        .server
+        .update_music_search_progress(UpdateMusicSearchProgressQuery {
+            music_genre_id: 1,
+            consumed: Some(5),
+            expected_position: None,
+        })
+        .await
+        .unwrap();
+
+    let result = context
+        .server
+        .update_music_search_progress(UpdateMusicSearchProgressQuery {
+            music_genre_id: 1,
+            consumed: Some(u32::MAX),
+            expected_position: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("現在の検索位置: 1000"));
+}
+
+#[tokio::test]
+async fn reset_music_search_progress_clears_completion() {
+    let context = setup(rock_artists(1)).await;
+    create_music_genre(&context, "ロック", "rock").await;
+    context
+        .server
+        .discover(DiscoverQuery {
+            music_genre_id: 1,
+            count: None,
+        })
+        .await
+        .unwrap();
+
+    let result = context
+        .server
+        .reset_music_search_progress(ResetMusicSearchProgressQuery {
+            music_genre_id: 1,
+            position: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(text(&result).contains("現在の検索位置: 0"));
+    assert!(!text(&result).contains("完了日時:"));
+}
+
+async fn insert_excluded_artists(
+    context: &common::TestContext,
+    ids: &[&str],
+) -> rmcp::model::CallToolResult {
+    context
+        .server
+        .insert_excluded_artists(InsertExcludedArtistsQuery {
+            artists: ids
+                .iter()
+                .map(|id| ExcludedArtistInput {
+                    id: id.to_string(),
+                    name: format!("Name {}", id),
+                })
+                .collect(),
+            reason: None,
+            category: None,
+        })
+        .await
+        .unwrap()
+}
+
+async fn create_music_genre(context: &common::TestContext, name: &str, search_key: &str) {
+    context
+        .server
+        .create_music_genre(CreateMusicGenreQuery {
+            name: name.to_string(),
+            search_key: search_key.to_string(),
+        })
+        .await
+        .unwrap();
+}
+
+async fn insert_music_search_progress(context: &common::TestContext, music_genre_id: u32) {
+    context
+        .server
+        .insert_music_search_progress(InsertMusicSearchProgressQuery { music_genre_id })
+        .await
+        .unwrap();
+}